@@ -0,0 +1,441 @@
+//a Imports
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use mod3d_base::Transformation;
+
+use crate::node::transformation_of_trs;
+use crate::Gltf;
+use crate::{AccessorIndex, AnimationIndex, BufferIndex, Indexable, NodeIndex};
+use crate::{Error, Named, Result, ValidationReport};
+
+//a Interpolation, AnimationPath
+//tp Interpolation
+/// The interpolation used between the keyframes of an animation sampler
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Interpolation {
+    /// Linear interpolation (spherical linear for rotations)
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "LINEAR"))]
+    Linear,
+    /// The value of the previous keyframe is held until the next
+    #[cfg_attr(feature = "serde", serde(rename = "STEP"))]
+    Step,
+    /// Cubic spline interpolation, with in- and out-tangents provided for
+    /// every keyframe
+    #[cfg_attr(feature = "serde", serde(rename = "CUBICSPLINE"))]
+    CubicSpline,
+}
+
+//tp AnimationPath
+/// The property of a node that an animation channel targets
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum AnimationPath {
+    /// The translation of the node (VEC3)
+    #[default]
+    Translation,
+    /// The rotation of the node (VEC4 quaternion, i j k r)
+    Rotation,
+    /// The scale of the node (VEC3)
+    Scale,
+    /// The morph target weights of the mesh of the node (SCALAR per target)
+    Weights,
+}
+
+//ip AnimationPath
+impl AnimationPath {
+    //ap width
+    /// The number of values that make up one keyframe value for the path,
+    /// if fixed
+    pub fn width(&self) -> Option<usize> {
+        match self {
+            Self::Translation | Self::Scale => Some(3),
+            Self::Rotation => Some(4),
+            Self::Weights => None,
+        }
+    }
+}
+
+//a GltfChannelTarget, GltfChannel, GltfAnimationSampler
+//tp GltfChannelTarget
+/// The target of an animation channel - a node and the property of it
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfChannelTarget {
+    /// The node that is animated; if None then the channel should be
+    /// ignored (it may be used by an extension)
+    pub node: Option<NodeIndex>,
+    /// The property of the node that is animated
+    pub path: AnimationPath,
+}
+
+//tp GltfChannel
+/// An animation channel, which connects a sampler of the animation to a
+/// target node property
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfChannel {
+    /// Index into the samplers of the animation
+    pub sampler: usize,
+    /// The target of the channel
+    pub target: GltfChannelTarget,
+}
+
+//tp GltfAnimationSampler
+/// An animation sampler, which combines keyframe times (input) with
+/// keyframe values (output) and an interpolation
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfAnimationSampler {
+    /// The accessor for the keyframe times, in seconds (SCALAR float)
+    pub input: AccessorIndex,
+    /// The interpolation between the keyframes
+    pub interpolation: Interpolation,
+    /// The accessor for the keyframe values
+    pub output: AccessorIndex,
+}
+
+//a GltfAnimation
+//tp GltfAnimation
+/// A type that contains the data from a Gltf Json 'Animation'
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfAnimation {
+    /// Optional name of the animation
+    pub name: String,
+    /// The channels of the animation
+    pub channels: Vec<GltfChannel>,
+    /// The samplers of the animation, referred to by the channels
+    pub samplers: Vec<GltfAnimationSampler>,
+    // optional: extensions, extras
+}
+
+//ip GltfAnimation
+impl GltfAnimation {
+    //ap channels
+    pub fn channels(&self) -> &[GltfChannel] {
+        &self.channels
+    }
+
+    //ap samplers
+    pub fn samplers(&self) -> &[GltfAnimationSampler] {
+        &self.samplers
+    }
+
+    //mp validate
+    /// Validate the animation, given the number of nodes and accessors in
+    /// the Gltf, adding any issues to the report
+    pub fn validate(
+        &self,
+        a: AnimationIndex,
+        num_nodes: usize,
+        num_accessors: usize,
+        report: &mut ValidationReport,
+    ) {
+        for (i, c) in self.channels.iter().enumerate() {
+            if c.sampler >= self.samplers.len() {
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    format!("/animations/{a}/channels/{i}/sampler"),
+                    format!("Channel sampler {0} out of range", c.sampler),
+                );
+            }
+            if let Some(n) = c.target.node {
+                if n.as_usize() >= num_nodes {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/animations/{a}/channels/{i}/target/node"),
+                        format!("Channel target node {n} out of range"),
+                    );
+                }
+            }
+        }
+        for (i, s) in self.samplers.iter().enumerate() {
+            if s.input.as_usize() >= num_accessors {
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    format!("/animations/{a}/samplers/{i}/input"),
+                    format!("Sampler input accessor {0} out of range", s.input),
+                );
+            }
+            if s.output.as_usize() >= num_accessors {
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    format!("/animations/{a}/samplers/{i}/output"),
+                    format!("Sampler output accessor {0} out of range", s.output),
+                );
+            }
+        }
+    }
+}
+
+//ip Named for GltfAnimation
+impl Named for GltfAnimation {
+    type Index = AnimationIndex;
+    fn is_name(&self, name: &str) -> bool {
+        self.name == name
+    }
+}
+
+//a AnimationPose
+//tp AnimationPose
+/// The result of evaluating an animation at a particular time
+///
+/// The transformations are the local transformations of the animated
+/// nodes, which can be set as the pose of each node before the Gltf is
+/// derived
+#[derive(Debug, Default)]
+pub struct AnimationPose {
+    /// The local transformation of each animated node
+    pub transformations: Vec<(NodeIndex, Transformation)>,
+    /// The morph target weights of each node with animated weights
+    pub weights: Vec<(NodeIndex, Vec<f32>)>,
+}
+
+//a AnimationEvaluator
+//ti Trs
+/// A translation, rotation (as i, j, k, r) and scale
+type Trs = ([f32; 3], [f32; 4], [f32; 3]);
+
+//ti EvalChannel
+/// A channel of an animation with its keyframe data read from the buffers
+#[derive(Debug)]
+struct EvalChannel {
+    node: NodeIndex,
+    path: AnimationPath,
+    interpolation: Interpolation,
+    times: Vec<f32>,
+    values: Vec<f32>,
+    width: usize,
+}
+
+//ii EvalChannel
+impl EvalChannel {
+    //mi value
+    /// Get a keyframe value; for cubic splines, 'which' is 0 for the
+    /// in-tangent, 1 for the value, 2 for the out-tangent
+    fn value(&self, key: usize, which: usize) -> &[f32] {
+        let start = {
+            if self.interpolation == Interpolation::CubicSpline {
+                (key * 3 + which) * self.width
+            } else {
+                key * self.width
+            }
+        };
+        &self.values[start..start + self.width]
+    }
+
+    //mi sample
+    /// Sample the channel at a time, writing the result into 'result'
+    fn sample(&self, time: f32, result: &mut [f32]) {
+        let n = self.times.len();
+        let last = n - 1;
+        if n == 1 || time <= self.times[0] {
+            result.copy_from_slice(self.value(0, 1));
+            return;
+        }
+        if time >= self.times[last] {
+            result.copy_from_slice(self.value(last, 1));
+            return;
+        }
+        let k = self.times.partition_point(|t| *t <= time) - 1;
+        let dt = self.times[k + 1] - self.times[k];
+        let u = (time - self.times[k]) / dt;
+        match self.interpolation {
+            Interpolation::Step => {
+                result.copy_from_slice(self.value(k, 1));
+            }
+            Interpolation::Linear => {
+                let v0 = self.value(k, 1);
+                let v1 = self.value(k + 1, 1);
+                if self.path == AnimationPath::Rotation {
+                    slerp(v0, v1, u, result);
+                } else {
+                    for (i, r) in result.iter_mut().enumerate() {
+                        *r = v0[i] + (v1[i] - v0[i]) * u;
+                    }
+                }
+            }
+            Interpolation::CubicSpline => {
+                let p0 = self.value(k, 1);
+                let m0 = self.value(k, 2);
+                let p1 = self.value(k + 1, 1);
+                let m1 = self.value(k + 1, 0);
+                let u2 = u * u;
+                let u3 = u2 * u;
+                let h00 = 2. * u3 - 3. * u2 + 1.;
+                let h10 = u3 - 2. * u2 + u;
+                let h01 = -2. * u3 + 3. * u2;
+                let h11 = u3 - u2;
+                for (i, r) in result.iter_mut().enumerate() {
+                    *r = h00 * p0[i] + h10 * dt * m0[i] + h01 * p1[i] + h11 * dt * m1[i];
+                }
+                if self.path == AnimationPath::Rotation {
+                    normalize(result);
+                }
+            }
+        }
+    }
+}
+
+//tp AnimationEvaluator
+/// An evaluator for a Gltf animation
+///
+/// This reads all the keyframe data for the animation from the buffers
+/// when it is created; it can then be evaluated at any time to produce an
+/// [AnimationPose]
+#[derive(Debug)]
+pub struct AnimationEvaluator {
+    channels: Vec<EvalChannel>,
+    duration: f32,
+}
+
+//ip AnimationEvaluator
+impl AnimationEvaluator {
+    //cp new
+    /// Create a new evaluator for an animation, given a function that
+    /// provides the data for a buffer (if it is available)
+    pub fn new<'a, F>(gltf: &Gltf, animation: AnimationIndex, buffer: &F) -> Result<Self>
+    where
+        F: Fn(BufferIndex) -> Option<&'a [u8]>,
+    {
+        let animation = &gltf[animation];
+        let mut channels = vec![];
+        let mut duration: f32 = 0.;
+        for c in animation.channels() {
+            let Some(node) = c.target.node else {
+                continue;
+            };
+            let Some(sampler) = animation.samplers().get(c.sampler) else {
+                return Err(Error::BadJson(format!(
+                    "Animation channel for node {node} has sampler {0} out of range",
+                    c.sampler
+                )));
+            };
+            let times = gltf.accessor_f32s(sampler.input, buffer)?;
+            let values = gltf.accessor_f32s(sampler.output, buffer)?;
+            if times.is_empty() {
+                continue;
+            }
+            let values_per_key = {
+                if sampler.interpolation == Interpolation::CubicSpline {
+                    3 * times.len()
+                } else {
+                    times.len()
+                }
+            };
+            let width = values.len() / values_per_key;
+            if width == 0
+                || values.len() != width * values_per_key
+                || c.target.path.width().is_some_and(|w| w != width)
+            {
+                return Err(Error::BadJson(format!(
+                    "Animation channel for node {node} has keyframe values that do not match its times",
+                )));
+            }
+            duration = duration.max(times[times.len() - 1]);
+            channels.push(EvalChannel {
+                node,
+                path: c.target.path,
+                interpolation: sampler.interpolation,
+                times,
+                values,
+                width,
+            });
+        }
+        Ok(Self { channels, duration })
+    }
+
+    //ap duration
+    /// Get the duration of the animation - the time of the last keyframe of
+    /// any channel
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    //mp evaluate
+    /// Evaluate the animation at a time, producing the local
+    /// transformations of all the animated nodes
+    ///
+    /// Properties of an animated node that are not animated take the values
+    /// given for the node in the Gltf
+    pub fn evaluate(&self, gltf: &Gltf, time: f32) -> AnimationPose {
+        let mut trs: Vec<(NodeIndex, Trs)> = vec![];
+        let mut weights: Vec<(NodeIndex, Vec<f32>)> = vec![];
+        for c in &self.channels {
+            if c.path == AnimationPath::Weights {
+                let mut w = vec![0.; c.width];
+                c.sample(time, &mut w);
+                weights.push((c.node, w));
+                continue;
+            }
+            let i = {
+                if let Some(i) = trs.iter().position(|(n, _)| *n == c.node) {
+                    i
+                } else {
+                    trs.push((c.node, gltf[c.node].rest_trs()));
+                    trs.len() - 1
+                }
+            };
+            let (t, r, s) = &mut trs[i].1;
+            match c.path {
+                AnimationPath::Translation => c.sample(time, t),
+                AnimationPath::Rotation => c.sample(time, r),
+                AnimationPath::Scale => c.sample(time, s),
+                AnimationPath::Weights => (),
+            }
+        }
+        let transformations = trs
+            .into_iter()
+            .map(|(n, (t, r, s))| (n, transformation_of_trs(t, r, s)))
+            .collect();
+        AnimationPose {
+            transformations,
+            weights,
+        }
+    }
+}
+
+//a Quaternion functions
+//fi normalize
+/// Normalize a quaternion (or any vector) in place
+fn normalize(q: &mut [f32]) {
+    let l = q.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if l > 0. {
+        for x in q.iter_mut() {
+            *x /= l;
+        }
+    }
+}
+
+//fi slerp
+/// Spherical linear interpolation between two unit quaternions, taking the
+/// shortest path
+fn slerp(q0: &[f32], q1: &[f32], u: f32, result: &mut [f32]) {
+    let mut d: f32 = q0.iter().zip(q1.iter()).map(|(a, b)| a * b).sum();
+    let sign = if d < 0. { -1. } else { 1. };
+    d *= sign;
+    let (s0, s1) = {
+        if d > 0.9995 {
+            (1. - u, u)
+        } else {
+            let theta = d.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1. - u) * theta).sin() / sin_theta,
+                (u * theta).sin() / sin_theta,
+            )
+        }
+    };
+    for (i, r) in result.iter_mut().enumerate() {
+        *r = s0 * q0[i] + s1 * sign * q1[i];
+    }
+    normalize(result);
+}
@@ -0,0 +1,106 @@
+//a Imports
+use crate::utils::mat4_transform_point;
+
+//a Aabb
+//tp Aabb
+/// An axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    /// The minimum coordinates of the box
+    pub min: [f32; 3],
+    /// The maximum coordinates of the box
+    pub max: [f32; 3],
+}
+
+//ip Aabb
+impl Aabb {
+    //cp new
+    /// Create a new bounding box from its minimum and maximum coordinates
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self { min, max }
+    }
+
+    //cp of_min_max
+    /// Create a bounding box from accessor-style min and max slices, if they
+    /// have at least three components
+    pub fn of_min_max(min: &[f32], max: &[f32]) -> Option<Self> {
+        if min.len() < 3 || max.len() < 3 {
+            return None;
+        }
+        Some(Self::new(
+            [min[0], min[1], min[2]],
+            [max[0], max[1], max[2]],
+        ))
+    }
+
+    //mp union
+    /// Return the smallest bounding box that contains this and another
+    pub fn union(&self, other: &Self) -> Self {
+        let mut r = *self;
+        for i in 0..3 {
+            r.min[i] = r.min[i].min(other.min[i]);
+            r.max[i] = r.max[i].max(other.max[i]);
+        }
+        r
+    }
+
+    //mp extend
+    /// Extend the bounding box by (possibly negative) displacements of its
+    /// minimum and maximum coordinates
+    pub fn extend(&mut self, min: &[f32; 3], max: &[f32; 3]) {
+        for i in 0..3 {
+            self.min[i] += min[i];
+            self.max[i] += max[i];
+        }
+    }
+
+    //mp transformed
+    /// Return the bounding box of this box transformed by a column-major
+    /// 4x4 affine matrix
+    pub fn transformed(&self, m: &[f32; 16]) -> Self {
+        let mut r: Option<Self> = None;
+        for c in 0..8 {
+            let corner = [
+                if c & 1 == 0 { self.min[0] } else { self.max[0] },
+                if c & 2 == 0 { self.min[1] } else { self.max[1] },
+                if c & 4 == 0 { self.min[2] } else { self.max[2] },
+            ];
+            let p = mat4_transform_point(m, &corner);
+            let p = Self::new(p, p);
+            r = Some(r.map_or(p, |r| r.union(&p)));
+        }
+        r.unwrap()
+    }
+
+    //ap center
+    /// Get the center of the bounding box
+    pub fn center(&self) -> [f32; 3] {
+        [
+            0.5 * (self.min[0] + self.max[0]),
+            0.5 * (self.min[1] + self.max[1]),
+            0.5 * (self.min[2] + self.max[2]),
+        ]
+    }
+
+    //ap bounding_sphere
+    /// Get the bounding sphere of the box - centered on the box, with a
+    /// radius of half of its diagonal
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        let d2: f32 = (0..3).map(|i| (self.max[i] - self.min[i]).powi(2)).sum();
+        BoundingSphere {
+            center: self.center(),
+            radius: 0.5 * d2.sqrt(),
+        }
+    }
+}
+
+//a BoundingSphere
+//tp BoundingSphere
+/// A bounding sphere, given by its center and radius
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    /// The center of the sphere
+    pub center: [f32; 3],
+    /// The radius of the sphere
+    pub radius: f32,
+}
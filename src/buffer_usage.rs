@@ -6,7 +6,8 @@ use crate::{ODBufDataIndex, ODBufIndex};
 //a BufferUsage
 //tp BufferUsage
 /// This type monitors the usage of a buffer - which range of bytes
-/// are used for indices, and which range for vertex data
+/// are used for indices, which range for vertex data, and which range for
+/// image data
 ///
 /// Once a buffer is exposed to the client of the gltf (in a Vec<B>)
 /// this also maps to the index of that array; it also holds the
@@ -18,12 +19,17 @@ pub struct BufferUsage {
     vertex_data: Range<usize>,
     /// The range of the buffer used for index data
     index_data: Range<usize>,
+    /// The range of the buffer used for image data
+    image_data: Range<usize>,
     /// The index into the user buffer Vec that this refers to
     buffer_index: ODBufIndex,
     /// The index into the user BufferData Vec that the vertex data range uses
     vertex_bd: Option<ODBufDataIndex>,
     /// The index into the user BufferData Vec that the index data range uses
     index_bd: Option<ODBufDataIndex>,
+    /// Set if the buffer contents are required to materialise a synthetic
+    /// buffer, even if no range of it is used directly
+    required: bool,
 }
 
 //ip BufferUsage
@@ -40,10 +46,16 @@ impl BufferUsage {
         !self.index_data.is_empty()
     }
 
+    //ap has_image_data
+    /// Return true if this uses image data
+    pub fn has_image_data(&self) -> bool {
+        !self.image_data.is_empty()
+    }
+
     //ap is_used
     /// Return true if this buffer is used at all
     pub fn is_used(&self) -> bool {
-        self.has_vertex_data() || self.has_index_data()
+        self.has_vertex_data() || self.has_index_data() || self.has_image_data() || self.required
     }
 
     //ap buffer_index
@@ -92,6 +104,21 @@ impl BufferUsage {
                 &mut self.vertex_data
             }
         };
+        Self::extend_range(range, byte_start, byte_length);
+    }
+
+    //mp use_image_data
+    /// Record the use of a portion of a buffer for image data
+    ///
+    /// Image data is not exposed as buffer data, but the buffer must be
+    /// generated for it
+    pub fn use_image_data(&mut self, byte_start: usize, byte_length: usize) {
+        Self::extend_range(&mut self.image_data, byte_start, byte_length);
+    }
+
+    //fi extend_range
+    /// Extend a range to include a portion of the buffer
+    fn extend_range(range: &mut Range<usize>, byte_start: usize, byte_length: usize) {
         if std::ops::Range::<usize>::is_empty(range) {
             *range = byte_start..(byte_start + byte_length)
         } else {
@@ -99,6 +126,12 @@ impl BufferUsage {
         };
     }
 
+    //mp set_required
+    /// Mark the buffer as required, even if no range of it is used
+    pub fn set_required(&mut self) {
+        self.required = true;
+    }
+
     //mp set_buffer_index
     pub fn set_buffer_index(&mut self, buffer_index: ODBufIndex) {
         self.buffer_index = buffer_index;
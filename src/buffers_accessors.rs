@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::{deserialize, serialize};
 
 use crate::{BufferIndex, ViewIndex};
+use crate::{Error, Result};
 
 //a GltfBuffer
 //tp GltfBuffer
@@ -16,6 +17,9 @@ use crate::{BufferIndex, ViewIndex};
 pub struct GltfBuffer {
     /// The URI specified by the buffer; this might be a data:URI containing
     /// the data itself, or maybe a relative path to a binary data or image
+    ///
+    /// This is empty for the binary chunk buffer of a GLB file
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "String::is_empty"))]
     uri: String,
     /// The byte length of the buffer - any provided URI contents must be at
     /// least this length
@@ -105,6 +109,229 @@ impl GltfBufferView {
     }
 }
 
+//tp GltfSparseIndices
+/// The indices of the elements of an accessor that are replaced by a sparse
+/// accessor; these are tightly packed unsigned integers in a buffer view
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GltfSparseIndices {
+    /// The buffer view containing the indices
+    #[cfg_attr(feature = "serde", serde(rename = "bufferView"))]
+    pub buffer_view: ViewIndex,
+    /// Byte offset of the indices from the start of the buffer view
+    #[cfg_attr(feature = "serde", serde(rename = "byteOffset"))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub byte_offset: usize,
+    /// The type of the indices - which must be UInt8, UInt16 or UInt32
+    #[cfg_attr(feature = "serde", serde(rename = "componentType"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize::comp_type_to_ele_type")
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize::ele_type_to_comp_type")
+    )]
+    pub component_type: mod3d_base::BufferElementType,
+    // optional: extensions, extras
+}
+
+//tp GltfSparseValues
+/// The values of the elements of an accessor that are replaced by a sparse
+/// accessor; these are tightly packed elements of the accessor's type
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GltfSparseValues {
+    /// The buffer view containing the values
+    #[cfg_attr(feature = "serde", serde(rename = "bufferView"))]
+    pub buffer_view: ViewIndex,
+    /// Byte offset of the values from the start of the buffer view
+    #[cfg_attr(feature = "serde", serde(rename = "byteOffset"))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub byte_offset: usize,
+    // optional: extensions, extras
+}
+
+//tp GltfSparse
+/// The sparse storage of an accessor - a set of elements that replace those
+/// of the accessor (or of zeros, if the accessor has no buffer view)
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GltfSparse {
+    /// The number of elements that are replaced
+    pub count: usize,
+    /// The indices of the elements that are replaced
+    pub indices: GltfSparseIndices,
+    /// The values that replace the elements
+    pub values: GltfSparseValues,
+    // optional: extensions, extras
+}
+
+//ip GltfSparse
+impl GltfSparse {
+    //ap indices_byte_length
+    /// Return the number of bytes of the indices buffer view used
+    pub fn indices_byte_length(&self) -> usize {
+        self.count * self.indices.component_type.byte_length()
+    }
+
+    //mp overlay
+    /// Overlay the sparse values onto tightly packed accessor data, given
+    /// the contents of the indices and values buffer views
+    pub fn overlay(
+        &self,
+        data: &mut [u8],
+        ele_byte_size: usize,
+        indices: &[u8],
+        values: &[u8],
+    ) -> Result<()> {
+        let index_size = self.indices.component_type.byte_length();
+        let indices_end = self.indices.byte_offset + self.indices_byte_length();
+        let values_end = self.values.byte_offset + self.count * ele_byte_size;
+        if indices_end > indices.len() || values_end > values.len() {
+            return Err(Error::BufferTooShort);
+        }
+        for i in 0..self.count {
+            let s = self.indices.byte_offset + i * index_size;
+            let index = match index_size {
+                1 => indices[s] as usize,
+                2 => u16::from_le_bytes([indices[s], indices[s + 1]]) as usize,
+                _ => {
+                    u32::from_le_bytes([indices[s], indices[s + 1], indices[s + 2], indices[s + 3]])
+                        as usize
+                }
+            };
+            let d = index * ele_byte_size;
+            if d + ele_byte_size > data.len() {
+                return Err(Error::BadJson(format!(
+                    "Sparse accessor index {index} is beyond the accessor count",
+                )));
+            }
+            let s = self.values.byte_offset + i * ele_byte_size;
+            data[d..d + ele_byte_size].copy_from_slice(&values[s..s + ele_byte_size]);
+        }
+        Ok(())
+    }
+}
+
+//tp GltfAccessorType
+/// The type of the N-element data structure of a Gltf accessor - a scalar,
+/// vector or (column-major) matrix
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum GltfAccessorType {
+    /// A single component
+    #[default]
+    Scalar,
+    /// A 2-component vector
+    Vec2,
+    /// A 3-component vector
+    Vec3,
+    /// A 4-component vector
+    Vec4,
+    /// A 2x2 matrix
+    Mat2,
+    /// A 3x3 matrix
+    Mat3,
+    /// A 4x4 matrix
+    Mat4,
+}
+
+//ip GltfAccessorType
+impl GltfAccessorType {
+    //ap num_components
+    /// Get the number of components in the data structure - 4 for a MAT2,
+    /// for example
+    pub fn num_components(&self) -> usize {
+        self.num_rows() * self.num_columns()
+    }
+
+    //ap num_columns
+    /// Get the number of columns of the data structure; this is 1 for
+    /// scalars and vectors
+    pub fn num_columns(&self) -> usize {
+        match self {
+            Self::Mat2 => 2,
+            Self::Mat3 => 3,
+            Self::Mat4 => 4,
+            _ => 1,
+        }
+    }
+
+    //ap num_rows
+    /// Get the number of rows of the data structure (the number of
+    /// components in each column)
+    pub fn num_rows(&self) -> usize {
+        match self {
+            Self::Scalar => 1,
+            Self::Vec2 | Self::Mat2 => 2,
+            Self::Vec3 | Self::Mat3 => 3,
+            Self::Vec4 | Self::Mat4 => 4,
+        }
+    }
+
+    //ap is_matrix
+    /// Return true if the type is a matrix
+    pub fn is_matrix(&self) -> bool {
+        matches!(self, Self::Mat2 | Self::Mat3 | Self::Mat4)
+    }
+
+    //ap column_byte_stride
+    /// Get the byte offset between the columns of the data structure, for
+    /// components of a byte length
+    ///
+    /// The columns of a matrix must start on 4-byte boundaries, so the
+    /// columns of MAT2 of 8-bit components and of MAT3 of 8- or 16-bit
+    /// components are padded
+    pub fn column_byte_stride(&self, component_byte_length: usize) -> usize {
+        let column_byte_length = self.num_rows() * component_byte_length;
+        if self.is_matrix() {
+            column_byte_length.next_multiple_of(4)
+        } else {
+            column_byte_length
+        }
+    }
+
+    //ap byte_length
+    /// Get the byte length of the data structure (including any column
+    /// padding), for components of a byte length
+    pub fn byte_length(&self, component_byte_length: usize) -> usize {
+        self.num_columns() * self.column_byte_stride(component_byte_length)
+    }
+
+    //ap is_padded
+    /// Return true if the columns of the data structure are padded, for
+    /// components of a byte length
+    pub fn is_padded(&self, component_byte_length: usize) -> bool {
+        self.byte_length(component_byte_length) != self.num_components() * component_byte_length
+    }
+
+    //ap component_byte_offset
+    /// Get the byte offset of the n'th component of the data structure
+    /// (in column-major order), for components of a byte length
+    pub fn component_byte_offset(&self, n: usize, component_byte_length: usize) -> usize {
+        let rows = self.num_rows();
+        (n / rows) * self.column_byte_stride(component_byte_length)
+            + (n % rows) * component_byte_length
+    }
+}
+
+//ip Display for GltfAccessorType
+impl std::fmt::Display for GltfAccessorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scalar => write!(f, "SCALAR"),
+            Self::Vec2 => write!(f, "VEC2"),
+            Self::Vec3 => write!(f, "VEC3"),
+            Self::Vec4 => write!(f, "VEC4"),
+            Self::Mat2 => write!(f, "MAT2"),
+            Self::Mat3 => write!(f, "MAT3"),
+            Self::Mat4 => write!(f, "MAT4"),
+        }
+    }
+}
+
 //tp GltfAccessor
 /// A Gltf accessor which references a buffer view to provide the data for
 /// either indices or an atttribute for a vertex
@@ -133,7 +360,7 @@ pub struct GltfAccessor {
     /// The type of the element; in Gltf JSON this is encoded with a magic
     /// number; the default value is signed 32-bit integer
     #[cfg_attr(feature = "serde", serde(rename = "componentType"))]
-    // 5120-5126: s8, u8, s16, u16, s32, u32, f32 else s32
+    // 5120-5126: s8, u8, s16, u16, s32, u32, f32
     #[cfg_attr(
         feature = "serde",
         serde(deserialize_with = "deserialize::comp_type_to_ele_type")
@@ -144,18 +371,39 @@ pub struct GltfAccessor {
         serde(serialize_with = "serialize::ele_type_to_comp_type")
     )]
     component_type: mod3d_base::BufferElementType,
+    /// True if integer components are normalized to [0,1] (unsigned) or
+    /// [-1,1] (signed) when they are read
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    normalized: bool,
     #[cfg_attr(feature = "serde", serde(rename = "count"))]
     // minimum 1
     count: usize,
+    /// The type of the N-element data structure - SCALAR, VEC2, VEC3,
+    /// VEC4, MAT2, MAT3 or MAT4
     #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    accessor_type: GltfAccessorType,
+    /// Sparse storage of elements that replace those of the buffer view (or
+    /// zeros)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    sparse: Option<GltfSparse>,
+    /// The minimum value of each component of the accessor (if known)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    min: Vec<f32>,
+    /// The maximum value of each component of the accessor (if known)
     #[cfg_attr(
         feature = "serde",
-        serde(deserialize_with = "deserialize::type_to_num")
+        serde(default, skip_serializing_if = "Vec::is_empty")
     )]
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize::num_to_type"))]
-    // SCALAR, VEC2, VEC3, VEC5, MAT2, MAT3, MAT4, string
-    elements_per_data: usize,
-    // optional: normalized, max, min, sparse
+    max: Vec<f32>,
     // optional: name, extensions, extras
 }
 
@@ -165,7 +413,7 @@ impl GltfAccessor {
         byte_offset: usize,
         count: usize,
         component_type: mod3d_base::BufferElementType,
-        elements_per_data: usize,
+        accessor_type: GltfAccessorType,
     ) -> Self {
         let buffer_view = Some(buffer_view);
         Self {
@@ -173,7 +421,11 @@ impl GltfAccessor {
             byte_offset,
             count,
             component_type,
-            elements_per_data,
+            normalized: false,
+            accessor_type,
+            sparse: None,
+            min: vec![],
+            max: vec![],
         }
     }
 
@@ -187,6 +439,61 @@ impl GltfAccessor {
         self.byte_offset
     }
 
+    //ap sparse
+    pub fn sparse(&self) -> Option<&GltfSparse> {
+        self.sparse.as_ref()
+    }
+
+    //ap min
+    /// Get the minimum of each component of the accessor; this is empty if
+    /// it is not known
+    pub fn min(&self) -> &[f32] {
+        &self.min
+    }
+
+    //ap max
+    /// Get the maximum of each component of the accessor; this is empty if
+    /// it is not known
+    pub fn max(&self) -> &[f32] {
+        &self.max
+    }
+
+    //ap has_bounds
+    /// Return true if the accessor has both min and max of the correct size
+    pub fn has_bounds(&self) -> bool {
+        let n = self.elements_per_data();
+        self.min.len() == n && self.max.len() == n
+    }
+
+    //mp set_bounds
+    /// Set the min and max of the accessor from its contents (as returned
+    /// by [Self::read_f32s])
+    ///
+    /// glTF requires the bounds to be raw component values, so for a
+    /// normalized accessor the contents must be read without
+    /// normalization (as by [Self::read_raw_f32s_of_data])
+    pub fn set_bounds(&mut self, values: &[f32]) {
+        let n = self.elements_per_data();
+        if n == 0 || values.len() < n {
+            return;
+        }
+        self.min = values[0..n].to_vec();
+        self.max = values[0..n].to_vec();
+        for v in values.chunks_exact(n) {
+            for (i, v) in v.iter().enumerate() {
+                self.min[i] = self.min[i].min(*v);
+                self.max[i] = self.max[i].max(*v);
+            }
+        }
+    }
+
+    //ap is_synthetic
+    /// Return true if the data for the accessor is not simply in a buffer
+    /// view, and so must be materialised into a buffer of its own
+    pub fn is_synthetic(&self) -> bool {
+        self.buffer_view.is_none() || self.sparse.is_some()
+    }
+
     //ap count
     pub fn count(&self) -> usize {
         self.count
@@ -197,14 +504,36 @@ impl GltfAccessor {
         self.component_type
     }
 
+    //ap normalized
+    pub fn normalized(&self) -> bool {
+        self.normalized
+    }
+
+    //mp set_normalized
+    /// Set the accessor to have normalized integer components
+    pub fn set_normalized(&mut self, normalized: bool) {
+        self.normalized = normalized;
+    }
+
+    //ap accessor_type
+    /// Get the type of the elements of the accessor (scalar, vector or
+    /// matrix)
+    pub fn accessor_type(&self) -> GltfAccessorType {
+        self.accessor_type
+    }
+
     //ap ele_byte_size
+    /// Get the byte size of an element, including any matrix column
+    /// padding
     pub fn ele_byte_size(&self) -> usize {
-        self.elements_per_data * self.component_type().byte_length()
+        self.accessor_type
+            .byte_length(self.component_type().byte_length())
     }
 
     //ap elements_per_data
+    /// Get the number of components in each element (e.g. 4 for a MAT2)
     pub fn elements_per_data(&self) -> usize {
-        self.elements_per_data
+        self.accessor_type.num_components()
     }
 
     //ap byte_stride
@@ -218,8 +547,154 @@ impl GltfAccessor {
 
     //ap byte_view_end
     /// Return the byte 1 past the last view byte used
+    ///
+    /// An accessor with no elements uses no bytes, and this returns its
+    /// byte offset
     pub fn byte_view_end(&self, view_byte_stride: usize) -> usize {
+        if self.count == 0 {
+            return self.byte_offset;
+        }
         let byte_stride = self.byte_stride(view_byte_stride);
         self.byte_offset + byte_stride * (self.count - 1) + self.ele_byte_size()
     }
+
+    //mp read_f32s
+    /// Read the contents of the accessor as a Vec of f32, given the
+    /// buffer view and the data of the buffer that the view refers to
+    ///
+    /// Each element of the accessor provides [Self::elements_per_data]
+    /// values in the result (matrices in column-major order, without
+    /// padding)
+    ///
+    /// If the accessor does not specify a buffer view then its
+    /// contents are zeros
+    pub fn read_f32s(&self, view: Option<(&GltfBufferView, &[u8])>) -> Result<Vec<f32>> {
+        let num_values = self.count * self.elements_per_data();
+        let Some((bv, data)) = view else {
+            return Ok(vec![0.0; num_values]);
+        };
+        let byte_start = bv.byte_offset() + self.byte_offset;
+        let byte_stride = self.byte_stride(bv.byte_stride(0));
+        self.read_f32s_of_data(data, byte_start, byte_stride)
+    }
+
+    //mp read_f32s_of_data
+    /// Read the contents of the accessor as a Vec of f32, from data with
+    /// the first element at a byte offset and the given byte stride
+    pub fn read_f32s_of_data(
+        &self,
+        data: &[u8],
+        byte_start: usize,
+        byte_stride: usize,
+    ) -> Result<Vec<f32>> {
+        self.read_components(data, byte_start, byte_stride, self.normalized)
+    }
+
+    //mp read_raw_f32s_of_data
+    /// Read the contents of the accessor as a Vec of f32 of the raw
+    /// component values (i.e. without normalization, even if the accessor
+    /// is normalized), from data with the first element at a byte offset
+    /// and the given byte stride
+    pub fn read_raw_f32s_of_data(
+        &self,
+        data: &[u8],
+        byte_start: usize,
+        byte_stride: usize,
+    ) -> Result<Vec<f32>> {
+        self.read_components(data, byte_start, byte_stride, false)
+    }
+
+    //mi read_components
+    /// Read the components of the accessor as a Vec of f32, normalizing
+    /// integer components if required
+    fn read_components(
+        &self,
+        data: &[u8],
+        byte_start: usize,
+        byte_stride: usize,
+        normalized: bool,
+    ) -> Result<Vec<f32>> {
+        self.check_data(data, byte_start, byte_stride)?;
+        let ele_size = self.component_type.byte_length();
+        let n = self.elements_per_data();
+        let mut result = Vec::with_capacity(self.count * n);
+        for i in 0..self.count {
+            let start = byte_start + i * byte_stride;
+            for j in 0..n {
+                let s = start + self.accessor_type.component_byte_offset(j, ele_size);
+                result.push(read_component(
+                    self.component_type,
+                    normalized,
+                    &data[s..s + ele_size],
+                ));
+            }
+        }
+        Ok(result)
+    }
+
+    //mp read_bytes
+    /// Read the contents of the accessor as tightly packed bytes, from data
+    /// with the first element at a byte offset and the given byte stride
+    pub fn read_bytes(
+        &self,
+        data: &[u8],
+        byte_start: usize,
+        byte_stride: usize,
+    ) -> Result<Vec<u8>> {
+        self.check_data(data, byte_start, byte_stride)?;
+        let ele_byte_size = self.ele_byte_size();
+        let mut result = Vec::with_capacity(self.count * ele_byte_size);
+        for i in 0..self.count {
+            let start = byte_start + i * byte_stride;
+            result.extend_from_slice(&data[start..start + ele_byte_size]);
+        }
+        Ok(result)
+    }
+
+    //mi check_data
+    /// Check that data is large enough for all of the elements of the
+    /// accessor
+    fn check_data(&self, data: &[u8], byte_start: usize, byte_stride: usize) -> Result<()> {
+        if self.count > 0 {
+            let byte_end = byte_start + byte_stride * (self.count - 1) + self.ele_byte_size();
+            if byte_end > data.len() {
+                return Err(Error::BufferTooShort);
+            }
+        }
+        Ok(())
+    }
+}
+
+//fi read_component
+/// Read a single component of a buffer element type from a slice of
+/// little-endian data, as an f32
+///
+/// If normalized then 8- and 16-bit integer components are mapped to [0,1]
+/// (unsigned) or [-1,1] (signed), as described in the Gltf specification
+fn read_component(ele_type: mod3d_base::BufferElementType, normalized: bool, data: &[u8]) -> f32 {
+    use mod3d_base::BufferElementType::*;
+    let (value, scale) = match ele_type {
+        Int8 => (data[0] as i8 as f32, 127.0),
+        UInt8 => (data[0] as f32, 255.0),
+        Int16 => (i16::from_le_bytes([data[0], data[1]]) as f32, 32767.0),
+        UInt16 => (u16::from_le_bytes([data[0], data[1]]) as f32, 65535.0),
+        Int32 => (
+            i32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f32,
+            1.0,
+        ),
+        UInt32 => (
+            u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f32,
+            1.0,
+        ),
+        Float32 => (
+            f32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+            1.0,
+        ),
+        _ => (0.0, 1.0),
+    };
+    if normalized {
+        (value / scale).max(-1.0)
+    } else {
+        value
+    }
 }
@@ -0,0 +1,240 @@
+//a Imports
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Named;
+use crate::{CameraIndex, ValidationReport};
+
+//a CameraType
+//tp CameraType
+/// The type of a Gltf camera
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CameraType {
+    /// A perspective camera, described by its 'perspective' property
+    #[default]
+    Perspective,
+    /// An orthographic camera, described by its 'orthographic' property
+    Orthographic,
+}
+
+//a GltfPerspective, GltfOrthographic
+//tp GltfPerspective
+/// The properties of a perspective camera
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfPerspective {
+    /// The aspect ratio (width over height) of the field of view; if None
+    /// then the aspect ratio of the viewport should be used
+    #[cfg_attr(feature = "serde", serde(rename = "aspectRatio"))]
+    pub aspect_ratio: Option<f32>,
+    /// The vertical field of view in radians
+    pub yfov: f32,
+    /// The distance to the far clipping plane; if None then the projection
+    /// is infinite
+    pub zfar: Option<f32>,
+    /// The distance to the near clipping plane
+    pub znear: f32,
+}
+
+//tp GltfOrthographic
+/// The properties of an orthographic camera
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfOrthographic {
+    /// The horizontal half-width of the view
+    pub xmag: f32,
+    /// The vertical half-height of the view
+    pub ymag: f32,
+    /// The distance to the far clipping plane
+    pub zfar: f32,
+    /// The distance to the near clipping plane
+    pub znear: f32,
+}
+
+//a GltfCamera
+//tp GltfCamera
+/// A type that contains the data from a Gltf Json 'Camera'
+///
+/// A camera looks down its local -Z axis, with +Y up; it is placed in a
+/// scene by the global transformation of a node that refers to it
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GltfCamera {
+    /// Optional name of the camera
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: String,
+    /// The type of the camera - which of perspective or orthographic must
+    /// be provided; this is required in the Gltf Json
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub camera_type: CameraType,
+    /// The perspective properties, for a perspective camera
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub perspective: Option<GltfPerspective>,
+    /// The orthographic properties, for an orthographic camera
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub orthographic: Option<GltfOrthographic>,
+    // optional: extensions, extras
+}
+
+//ip GltfCamera
+impl GltfCamera {
+    //cp of_perspective
+    /// Create a perspective camera
+    pub fn of_perspective(perspective: GltfPerspective) -> Self {
+        Self {
+            camera_type: CameraType::Perspective,
+            perspective: Some(perspective),
+            ..Default::default()
+        }
+    }
+
+    //cp of_orthographic
+    /// Create an orthographic camera
+    pub fn of_orthographic(orthographic: GltfOrthographic) -> Self {
+        Self {
+            camera_type: CameraType::Orthographic,
+            orthographic: Some(orthographic),
+            ..Default::default()
+        }
+    }
+
+    //ap camera_type
+    pub fn camera_type(&self) -> CameraType {
+        self.camera_type
+    }
+
+    //ap perspective
+    pub fn perspective(&self) -> Option<&GltfPerspective> {
+        self.perspective.as_ref()
+    }
+
+    //ap orthographic
+    pub fn orthographic(&self) -> Option<&GltfOrthographic> {
+        self.orthographic.as_ref()
+    }
+
+    //mp validate
+    /// Validate the camera - check it has the properties for its type (and
+    /// not those of the other type), and that they are legal - adding any
+    /// issues to the report
+    pub fn validate(&self, c: CameraIndex, report: &mut ValidationReport) {
+        match self.camera_type {
+            CameraType::Perspective => {
+                if self.orthographic.is_some() {
+                    report.error(
+                        "ONE_OF_MISMATCH",
+                        format!("/cameras/{c}/orthographic"),
+                        "Camera is perspective but has an orthographic property",
+                    );
+                }
+                let Some(p) = &self.perspective else {
+                    report.error(
+                        "CAMERA_NO_PROPERTIES",
+                        format!("/cameras/{c}"),
+                        "Camera is perspective but has no perspective property",
+                    );
+                    return;
+                };
+                if p.yfov <= 0. || p.znear <= 0. || p.aspect_ratio.is_some_and(|a| a <= 0.) {
+                    report.error(
+                        "VALUE_NOT_IN_RANGE",
+                        format!("/cameras/{c}/perspective"),
+                        "Camera has an illegal perspective yfov, znear or aspectRatio",
+                    );
+                }
+                if p.zfar.is_some_and(|f| f <= p.znear) {
+                    report.error(
+                        "CAMERA_ZFAR_LEQUAL_ZNEAR",
+                        format!("/cameras/{c}/perspective/zfar"),
+                        "Camera has a perspective zfar that is not beyond znear",
+                    );
+                }
+            }
+            CameraType::Orthographic => {
+                if self.perspective.is_some() {
+                    report.error(
+                        "ONE_OF_MISMATCH",
+                        format!("/cameras/{c}/perspective"),
+                        "Camera is orthographic but has a perspective property",
+                    );
+                }
+                let Some(o) = &self.orthographic else {
+                    report.error(
+                        "CAMERA_NO_PROPERTIES",
+                        format!("/cameras/{c}"),
+                        "Camera is orthographic but has no orthographic property",
+                    );
+                    return;
+                };
+                if o.xmag == 0. || o.ymag == 0. || o.znear < 0. || o.zfar <= o.znear {
+                    report.error(
+                        "VALUE_NOT_IN_RANGE",
+                        format!("/cameras/{c}/orthographic"),
+                        "Camera has illegal orthographic properties",
+                    );
+                }
+            }
+        }
+    }
+
+    //mp projection
+    /// Get the column-major projection matrix for the camera, as given in
+    /// the Gltf specification
+    ///
+    /// The aspect ratio of the camera is used if it has one; else the
+    /// viewport aspect ratio provided is used
+    ///
+    /// The camera must be valid; if it does not have the properties for its
+    /// type then the matrix is all zeros
+    pub fn projection(&self, viewport_aspect_ratio: f32) -> [f32; 16] {
+        let mut m = [0.; 16];
+        match self.camera_type {
+            CameraType::Orthographic => {
+                let Some(o) = self.orthographic() else {
+                    return m;
+                };
+                m[0] = 1. / o.xmag;
+                m[5] = 1. / o.ymag;
+                m[10] = 2. / (o.znear - o.zfar);
+                m[14] = (o.zfar + o.znear) / (o.znear - o.zfar);
+                m[15] = 1.;
+            }
+            CameraType::Perspective => {
+                let Some(p) = self.perspective() else {
+                    return m;
+                };
+                let aspect_ratio = p.aspect_ratio.unwrap_or(viewport_aspect_ratio);
+                let t = (0.5 * p.yfov).tan();
+                m[0] = 1. / (aspect_ratio * t);
+                m[5] = 1. / t;
+                m[11] = -1.;
+                if let Some(zfar) = p.zfar {
+                    m[10] = (zfar + p.znear) / (p.znear - zfar);
+                    m[14] = 2. * zfar * p.znear / (p.znear - zfar);
+                } else {
+                    m[10] = -1.;
+                    m[14] = -2. * p.znear;
+                }
+            }
+        }
+        m
+    }
+}
+
+//ip Named for GltfCamera
+impl Named for GltfCamera {
+    type Index = CameraIndex;
+    fn is_name(&self, name: &str) -> bool {
+        self.name == name
+    }
+}
@@ -4,36 +4,92 @@ use std::collections::HashMap;
 use serde;
 use serde::{Deserialize, Deserializer};
 
+use crate::primitives_meshes::{GltfAttributes, GltfTargets};
 use crate::AccessorIndex;
 
 //a Deserializer functions
+//fi attr_set
+/// Split a Gltf attribute name of the form SEMANTIC_n (such as TEXCOORD_2)
+/// into the semantic and the set index, if it is of that form
+fn attr_set(name: &str) -> Option<(&str, usize)> {
+    let (semantic, n) = name.rsplit_once('_')?;
+    if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if n.len() > 1 && n.starts_with('0') {
+        return None;
+    }
+    Some((semantic, n.parse().ok()?))
+}
+
+//fi attrs_of_map
+/// Map a Gltf attribute name to accessor index map to the attributes of a
+/// primitive or morph target
+///
+/// Attributes that correspond to a mod3d_base::VertexAttr are mapped to
+/// it; other texture coordinate, color, joint and weight sets, and
+/// application specific attributes (whose names start with an underscore)
+/// are kept by name as custom attributes
+fn attrs_of_map<E: serde::de::Error>(
+    m: HashMap<String, usize>,
+) -> std::result::Result<GltfAttributes, E> {
+    let mut r = GltfAttributes::default();
+    for (k, v) in m.into_iter() {
+        use mod3d_base::VertexAttr::*;
+        let va = match k.as_ref() {
+            "POSITION" => Some(Position),
+            "NORMAL" => Some(Normal),
+            "TANGENT" => Some(Tangent),
+            _ => match attr_set(&k) {
+                Some(("COLOR", 0)) => Some(Color),
+                Some(("JOINTS", 0)) => Some(Joints),
+                Some(("WEIGHTS", 0)) => Some(Weights),
+                Some(("TEXCOORD", 0)) => Some(TexCoords0),
+                Some(("TEXCOORD", 1)) => Some(TexCoords1),
+                Some(("COLOR" | "JOINTS" | "WEIGHTS" | "TEXCOORD", _)) => None,
+                _ if k.starts_with('_') => None,
+                _ => {
+                    return Err(E::custom(format!("Unknown attribute {k}")));
+                }
+            },
+        };
+        match va {
+            Some(va) => r.vertex.push((va, v.into())),
+            None => r.custom.push((k, v.into())),
+        }
+    }
+    r.custom.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(r)
+}
+
 //fi attr_to_attr
-/// Map an array of Gltf string attribute name/value pairs to a Vec of
-/// tuples of mod3d_base::VertexAttr and AccessorIndex
-pub fn attr_to_attr<'de, D>(
-    de: D,
-) -> std::result::Result<Vec<(mod3d_base::VertexAttr, AccessorIndex)>, D::Error>
+/// Map an array of Gltf string attribute name/value pairs to the
+/// attributes of a primitive
+pub(crate) fn attr_to_attr<'de, D>(de: D) -> std::result::Result<GltfAttributes, D::Error>
 where
     D: Deserializer<'de>,
 {
     let m: HashMap<String, usize> = Deserialize::deserialize(de)?;
-    let mut r = vec![];
-    for (k, v) in m.into_iter() {
-        use mod3d_base::VertexAttr::*;
-        let k = match k.as_ref() {
-            "POSITION" => Position,
-            "NORMAL" => Normal,
-            "COLOR_0" => Color,
-            "TANGENT" => Tangent,
-            "JOINTS_0" => Joints,
-            "WEIGHTS_0" => Weights,
-            "TEXCOORD_0" => TexCoords0,
-            "TEXCOORD_1" => TexCoords1,
-            _ => {
-                return Err(serde::de::Error::custom(format!("Unknown attribute {k}")));
-            }
-        };
-        r.push((k, v.into()));
+    attrs_of_map(m)
+}
+
+//fi targets_to_attrs
+/// Map an array of Gltf morph targets, each of which is a map of attribute
+/// name to accessor index, to the morph targets of a primitive
+///
+/// The attributes of each target are mapped as for the attributes of the
+/// primitive itself; those that are not mod3d_base::VertexAttr (such as
+/// TEXCOORD_2) are kept by name
+pub(crate) fn targets_to_attrs<'de, D>(de: D) -> std::result::Result<GltfTargets, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let targets: Vec<HashMap<String, usize>> = Deserialize::deserialize(de)?;
+    let mut r = GltfTargets::default();
+    for m in targets.into_iter() {
+        let t = attrs_of_map(m)?;
+        r.vertex.push(t.vertex);
+        r.custom.push(t.custom);
     }
     Ok(r)
 }
@@ -84,11 +140,11 @@ where
     use mod3d_base::BufferElementType::*;
     Ok(match c {
         5120 => Int8,
-        5121 => Int8, // unsigned
+        5121 => UInt8,
         5122 => Int16,
-        5123 => Int16, // unsigned
+        5123 => UInt16,
         5124 => Int32,
-        5125 => Int32, // unsigned
+        5125 => UInt32,
         5126 => Float32,
         _ => {
             return Err(serde::de::Error::custom(format!(
@@ -104,26 +160,32 @@ pub fn ele_type_s32() -> mod3d_base::BufferElementType {
     mod3d_base::BufferElementType::Int32
 }
 
-//fi type_to_num
-/// Map a Gltf JSON element array/scalar type name to a number of elements
-pub fn type_to_num<'de, D>(de: D) -> std::result::Result<usize, D::Error>
+pub fn f32_one() -> f32 {
+    1.0
+}
+
+//fi gl_enum
+/// Map a Gltf JSON GL enumeration integer to a type that can be created from
+/// it
+pub fn gl_enum<'de, D, T>(de: D) -> std::result::Result<T, D::Error>
 where
     D: Deserializer<'de>,
+    T: TryFrom<u32, Error = String>,
 {
-    let s: String = Deserialize::deserialize(de)?;
-    match s.as_ref() {
-        "SCALAR" => Ok(1),
-        "VEC2" => Ok(2),
-        "VEC3" => Ok(3),
-        "VEC4" => Ok(4),
-        "MAT2" => Ok(2),
-        "MAT3" => Ok(9),
-        "MAT4" => Ok(16),
-        _ => Err(format!("Unknown accessor type {s}")),
-    }
-    .map_err(serde::de::Error::custom)
+    let c: u32 = Deserialize::deserialize(de)?;
+    T::try_from(c).map_err(serde::de::Error::custom)
 }
 
-pub fn f32_one() -> f32 {
-    1.0
+//fi opt_gl_enum
+/// Map an optional Gltf JSON GL enumeration integer to a type that can be
+/// created from it
+pub fn opt_gl_enum<'de, D, T>(de: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<u32, Error = String>,
+{
+    let c: Option<u32> = Deserialize::deserialize(de)?;
+    c.map(T::try_from)
+        .transpose()
+        .map_err(serde::de::Error::custom)
 }
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::{ImageIndex, ValidationReport};
+
 #[derive(Error, Debug)]
 pub enum GltfError {
     #[error("IO error")]
@@ -9,6 +11,8 @@ pub enum GltfError {
     Json(#[from] serde_json::Error),
     #[error("Bad GLTF Json")]
     BadJson(String),
+    #[error("Gltf validation failed: {0}")]
+    Validation(ValidationReport),
     #[error("Bad UTF8 in GLTF Json")]
     Utf8(#[from] std::str::Utf8Error),
     #[error("Bad base64 in GLTF Json")]
@@ -17,20 +21,35 @@ pub enum GltfError {
     BufferTooShort,
     #[error("Buffer could not be read")]
     BufferRead,
-    #[error("Failed to load image {reason}")]
-    ImageLoad { reason: String },
+    #[error("URI {0} is not a relative path within the Gltf directory")]
+    BadUri(String),
+    #[error("No resource found for URI {0}")]
+    UnresolvedUri(String),
+    #[error("Failed to load image {image}")]
+    ImageLoad {
+        image: ImageIndex,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
     #[error("Bad GLB header")]
     GlbHdr,
     #[error("Bad GLB Json header")]
     GlbJsonHdr,
     #[error("IO error reading GLB Json")]
     GlbJsonIo(std::io::Error),
-    #[error("GLB json ends beyond file extent")]
+    #[error("GLB json is longer than permitted")]
     GlbJsonLength,
     #[error("Bad GLB binary buffer header")]
     GlbBinHdr,
     #[error("IO error reading GLB binary")]
     GlbBinIo(std::io::Error),
+    #[error("GLB file would exceed 4GB")]
+    GlbTooLarge,
+    #[error("GLB file ends before the length given in its header")]
+    GlbTruncated,
+    #[error("GLB chunk extends beyond the length given in the file header")]
+    GlbChunkLength,
+    #[error("GLB chunk length is not a multiple of 4 bytes")]
+    GlbChunkAlignment,
     #[error("unknown data store error")]
     Unknown,
 }
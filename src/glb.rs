@@ -38,102 +38,291 @@ use serde_json::Value as JsonValue;
 
 use crate::{Error, Gltf, Result};
 
-struct GlbLoader<'file, F: std::io::Read, B> {
+//a GlbChunks
+//tp GlbChunkType
+/// The type of a chunk in a GLB file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlbChunkType {
+    /// The Json chunk, which must be the first chunk
+    Json,
+    /// The binary buffer chunk, which (if present) must be the second chunk
+    Bin,
+    /// An extension chunk of a type that is not known
+    Unknown(u32),
+}
+
+//ip From<u32> for GlbChunkType
+impl From<u32> for GlbChunkType {
+    fn from(chunk_type: u32) -> Self {
+        match chunk_type {
+            0x4E4F534A => Self::Json,
+            0x004E4942 => Self::Bin,
+            _ => Self::Unknown(chunk_type),
+        }
+    }
+}
+
+//tp GlbChunk
+/// The header of a chunk in a GLB file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlbChunk {
+    /// The type of the chunk
+    pub chunk_type: GlbChunkType,
+    /// The byte length of the chunk data (a multiple of 4)
+    pub byte_length: usize,
+}
+
+//tp GlbChunks
+/// An iterator over the chunks of a GLB file, given a reader positioned at
+/// the start of the file
+///
+/// Each chunk header is validated (length is a multiple of 4, and the
+/// chunk lies within the length given by the GLB header) before it is
+/// returned; the data of the chunk may then be read with [Self::read_data]
+/// or [Self::read_data_with]. If it is not read then it is skipped when the
+/// next chunk is requested. The iteration ends when the whole of the length
+/// given by the GLB header has been consumed; if the file ends before then
+/// an [Error::GlbTruncated] is returned.
+pub struct GlbChunks<'file, F: std::io::Read> {
     file: &'file mut F,
+    /// The byte length of the file, from the GLB header
+    byte_length: usize,
+    /// The byte offset of the file that has been consumed (including the data
+    /// of the current chunk)
+    byte_offset: usize,
+    /// The number of bytes of the current chunk's data that have not been read
+    pending: usize,
+    /// Set if an error has been returned, to end the iteration
+    failed: bool,
+}
+
+//fi map_io_error
+/// Map an IO error to an Error, treating an unexpected end of file as a
+/// truncated GLB file
+pub(crate) fn map_io_error(e: std::io::Error, f: fn(std::io::Error) -> Error) -> Error {
+    if e.kind() == std::io::ErrorKind::UnexpectedEof {
+        Error::GlbTruncated
+    } else {
+        f(e)
+    }
+}
+
+//fi parse_glb_header
+/// Validate the 12-byte header of a GLB file, and return the byte length of
+/// the file that it gives
+pub(crate) fn parse_glb_header(hdr: &[u8; 12]) -> Result<usize> {
+    if hdr[0..8] != [0x67, 0x6c, 0x54, 0x46, 0x02, 0x00, 0x00, 0x00] {
+        return Err(Error::GlbHdr);
+    }
+    let byte_length = u32::from_le_bytes([hdr[8], hdr[9], hdr[10], hdr[11]]) as usize;
+    if byte_length < 12 {
+        return Err(Error::GlbHdr);
+    }
+    Ok(byte_length)
+}
+
+//fi parse_chunk_header
+/// Validate the 8-byte header of a GLB chunk that starts at a byte offset
+/// in a GLB file of a byte length, and return the chunk
+pub(crate) fn parse_chunk_header(
+    hdr: &[u8; 8],
+    byte_offset: usize,
+    file_byte_length: usize,
+) -> Result<GlbChunk> {
+    let byte_length = u32::from_le_bytes([hdr[0], hdr[1], hdr[2], hdr[3]]) as usize;
+    let chunk_type = u32::from_le_bytes([hdr[4], hdr[5], hdr[6], hdr[7]]).into();
+    if byte_length % 4 != 0 {
+        return Err(Error::GlbChunkAlignment);
+    }
+    if byte_offset + 8 + byte_length > file_byte_length {
+        return Err(Error::GlbChunkLength);
+    }
+    Ok(GlbChunk {
+        chunk_type,
+        byte_length,
+    })
+}
+
+//ip GlbChunks
+impl<'file, F: std::io::Read> GlbChunks<'file, F> {
+    //cp new
+    /// Create a new chunk iterator by reading and validating the GLB header
+    /// from the file
+    pub fn new(file: &'file mut F) -> Result<Self> {
+        let mut hdr = [0; 12];
+        file.read_exact(&mut hdr)
+            .map_err(|e| map_io_error(e, Error::Io))?;
+        let byte_length = parse_glb_header(&hdr)?;
+        Ok(Self {
+            file,
+            byte_length,
+            byte_offset: 12,
+            pending: 0,
+            failed: false,
+        })
+    }
+
+    //ap byte_length
+    /// Get the byte length of the GLB file given by its header
+    pub fn byte_length(&self) -> usize {
+        self.byte_length
+    }
+
+    //mi skip_pending
+    /// Skip any data of the current chunk that has not been read
+    fn skip_pending(&mut self) -> Result<()> {
+        if self.pending > 0 {
+            let n = std::io::copy(
+                &mut std::io::Read::take(&mut *self.file, self.pending as u64),
+                &mut std::io::sink(),
+            )?;
+            if n < self.pending as u64 {
+                return Err(Error::GlbTruncated);
+            }
+            self.pending = 0;
+        }
+        Ok(())
+    }
+
+    //mi next_chunk
+    /// Read the header of the next chunk, if there is one
+    fn next_chunk(&mut self) -> Result<Option<GlbChunk>> {
+        self.skip_pending()?;
+        if self.byte_offset == self.byte_length {
+            return Ok(None);
+        }
+        if self.byte_offset + 8 > self.byte_length {
+            return Err(Error::GlbChunkLength);
+        }
+        let mut hdr = [0; 8];
+        self.file
+            .read_exact(&mut hdr)
+            .map_err(|e| map_io_error(e, Error::Io))?;
+        let chunk = parse_chunk_header(&hdr, self.byte_offset, self.byte_length)?;
+        self.byte_offset += 8 + chunk.byte_length;
+        self.pending = chunk.byte_length;
+        Ok(Some(chunk))
+    }
+
+    //mp read_data
+    /// Read all the data of the current chunk
+    pub fn read_data(&mut self) -> Result<Vec<u8>> {
+        let mut data = vec![0; self.pending];
+        self.file
+            .read_exact(&mut data)
+            .map_err(|e| map_io_error(e, Error::Io))?;
+        self.pending = 0;
+        Ok(data)
+    }
+
+    //mp read_data_with
+    /// Read the data of the current chunk with a reader function, which is
+    /// invoked with the file and the byte length of the data, and which must
+    /// read exactly that many bytes (or return None and read nothing)
+    pub fn read_data_with<B, BR>(&mut self, buf_reader: &BR) -> Result<Option<B>>
+    where
+        BR: Fn(&mut F, usize) -> std::result::Result<Option<B>, std::io::Error>,
+    {
+        let b =
+            buf_reader(self.file, self.pending).map_err(|e| map_io_error(e, Error::GlbBinIo))?;
+        if b.is_some() {
+            self.pending = 0;
+        }
+        Ok(b)
+    }
+}
+
+//ip Iterator for GlbChunks
+impl<F: std::io::Read> Iterator for GlbChunks<'_, F> {
+    type Item = Result<GlbChunk>;
+    fn next(&mut self) -> Option<Result<GlbChunk>> {
+        if self.failed {
+            return None;
+        }
+        match self.next_chunk() {
+            Ok(chunk) => chunk.map(Ok),
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+//a GlbLoader
+//ti GlbLoader
+struct GlbLoader<'file, F: std::io::Read, B> {
+    chunks: GlbChunks<'file, F>,
     max_json_length: usize,
-    byte_length: u32,
     json_value: JsonValue,
     buffer_0: Option<B>,
 }
+
+//ii GlbLoader
 impl<'file, F: std::io::Read, B> GlbLoader<'file, F, B> {
     //cp new
     /// Create a new GlbLoader given a file and maximum length
-    /// expected of the Json within it
-    fn new(file: &'file mut F, max_json_length: usize) -> Self {
-        let byte_length = 0;
+    /// expected of the Json within it, reading and validating the GLB
+    /// header
+    fn new(file: &'file mut F, max_json_length: usize) -> Result<Self> {
+        let chunks = GlbChunks::new(file)?;
         let json_value = json!(null);
         let buffer_0 = None;
-        Self {
-            file,
+        Ok(Self {
+            chunks,
             max_json_length,
-            byte_length,
             json_value,
             buffer_0,
-        }
-    }
-
-    //mp read_glb_hdr
-    /// Read the GLB header from the file for the loader and validate
-    /// it
-    fn read_glb_hdr(&mut self) -> Result<()> {
-        let mut hdr = [0; 12];
-        self.file.read_exact(&mut hdr)?;
-        if hdr[0..8] != [0x67, 0x6c, 0x54, 0x46, 0x02, 0x00, 0x00, 0x00] {
-            return Err(Error::GlbHdr);
-        }
-        let byte_length = (hdr[8] as u32)
-            | ((hdr[9] as u32) << 8)
-            | ((hdr[10] as u32) << 16)
-            | ((hdr[11] as u32) << 24);
-        self.byte_length = byte_length;
-        Ok(())
+        })
     }
 
     //mp read_json
-    /// Read the Json chunk of the GLB file (assuming the header has
-    /// been read), and validate the lengtht of it, and parse the Json
+    /// Read the Json chunk of the GLB file, which must be the first chunk,
+    /// and validate the length of it, and parse the Json
     ///
     /// Do not at this point attempt to validate the Json to be Gltf Json
     fn read_json(&mut self) -> Result<()> {
-        let mut hdr = [0; 8];
-        self.file.read_exact(&mut hdr).map_err(Error::GlbJsonIo)?;
-        if hdr[4..8] != [0x4a, 0x53, 0x4f, 0x4e] {
+        let chunk = self.chunks.next().ok_or(Error::GlbJsonHdr)??;
+        if chunk.chunk_type != GlbChunkType::Json {
             return Err(Error::GlbJsonHdr);
         }
-        let json_byte_length = (hdr[0] as u32)
-            | ((hdr[1] as u32) << 8)
-            | ((hdr[2] as u32) << 16)
-            | ((hdr[3] as u32) << 24);
-        let json_byte_length = json_byte_length as usize;
-        if json_byte_length > self.max_json_length {
-            return Err(Error::GlbJsonHdr);
-        }
-        let mut buffer = vec![0; json_byte_length];
-        let bytes_read = self.file.read(&mut buffer)?;
-        if bytes_read < json_byte_length {
+        if chunk.byte_length > self.max_json_length {
             return Err(Error::GlbJsonLength);
         }
+        let buffer = self.chunks.read_data().map_err(|e| match e {
+            Error::Io(e) => Error::GlbJsonIo(e),
+            e => e,
+        })?;
         self.json_value = serde_json::from_str(std::str::from_utf8(&buffer)?)?;
         Ok(())
     }
 
-    //mp read_buffer
-    /// Read the 'binary' buffer  chunk of the GLB if it is present
+    //mp read_chunks
+    /// Read the remaining chunks of the GLB file
     ///
-    /// This does *NOT* support GLB files with a Json chunk and an
-    /// extension chunk without a binary chunk. It consumes the header
-    /// of the next Glb chunk and checks that it is a binary chunk; if
-    /// no header is there (no binary) then all is okay, but if there
-    /// is no binary chunk but there is an extension chunk then its
-    /// header will have been consumed
-    fn read_buffer<BR>(&mut self, buf_reader: &BR) -> Result<()>
+    /// The 'binary' buffer chunk is read if it is present, in which case it
+    /// must immediately follow the Json chunk; extension chunks of unknown
+    /// types are skipped
+    fn read_chunks<BR>(&mut self, buf_reader: &BR) -> Result<()>
     where
         BR: Fn(&mut F, usize) -> std::result::Result<Option<B>, std::io::Error>,
     {
-        let mut hdr = [0; 8];
-        let n = self.file.read(&mut hdr).map_err(Error::GlbJsonIo)?;
-        if n == 0 {
-            return Ok(());
-        }
-        if hdr[4..8] != [0x42, 0x49, 0x4e, 0x00] {
-            return Err(Error::GlbBinHdr);
-        }
-        let bin_byte_length = (hdr[0] as u32)
-            | ((hdr[1] as u32) << 8)
-            | ((hdr[2] as u32) << 16)
-            | ((hdr[3] as u32) << 24);
-        let bin_byte_length = bin_byte_length as usize;
-        self.buffer_0 = buf_reader(self.file, bin_byte_length).map_err(Error::GlbBinIo)?;
+        let mut first = true;
+        while let Some(chunk) = self.chunks.next() {
+            match chunk?.chunk_type {
+                GlbChunkType::Bin if first => {
+                    self.buffer_0 = self.chunks.read_data_with(buf_reader)?;
+                }
+                GlbChunkType::Bin => {
+                    return Err(Error::GlbBinHdr);
+                }
+                GlbChunkType::Json => {
+                    return Err(Error::GlbJsonHdr);
+                }
+                GlbChunkType::Unknown(_) => (),
+            }
+            first = false;
+        }
         Ok(())
     }
 
@@ -146,14 +335,146 @@ impl<'file, F: std::io::Read, B> GlbLoader<'file, F, B> {
     }
 }
 
+//fp glb_load
+/// Load a GLB file, returning the Gltf and the binary buffer chunk (if
+/// any) as read by the buffer reader function
+///
+/// The buffer reader is invoked with the file and the byte length of the
+/// binary chunk; it must read exactly that many bytes, or return None
+/// having read nothing (in which case the chunk is skipped)
 pub fn glb_load<F, B, BR>(file: &mut F, b: &BR, max_json_length: usize) -> Result<(Gltf, Option<B>)>
 where
     F: std::io::Read,
     BR: Fn(&mut F, usize) -> std::result::Result<Option<B>, std::io::Error>,
 {
-    let mut loader = GlbLoader::new(file, max_json_length);
-    loader.read_glb_hdr()?;
+    let mut loader = GlbLoader::new(file, max_json_length)?;
     loader.read_json()?;
-    loader.read_buffer(b)?;
+    loader.read_chunks(b)?;
     loader.into_gltf()
 }
+
+//fp glb_load_slice
+/// Load a GLB file from a byte slice (such as a memory-mapped file),
+/// returning the Gltf and the binary buffer chunk (if any) as a slice of
+/// the data - it is not copied
+///
+/// The binary chunk may be provided to [crate::ObjectData::gen_buffers] as
+/// a `Cow::Borrowed`, so that the buffers generated borrow from the data
+pub fn glb_load_slice<'a>(
+    data: &'a [u8],
+    max_json_length: usize,
+) -> Result<(Gltf, Option<&'a [u8]>)> {
+    let buf_reader = |file: &mut &'a [u8], byte_length: usize| -> std::io::Result<_> {
+        if file.len() < byte_length {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        let (chunk, rest) = file.split_at(byte_length);
+        *file = rest;
+        Ok(Some(chunk))
+    };
+    let mut file = data;
+    glb_load(&mut file, &buf_reader, max_json_length)
+}
+
+//fi write_chunk
+/// Write a GLB chunk - its header, data, and padding to a multiple of 4
+/// bytes with the pad byte
+fn write_chunk<W: std::io::Write>(
+    file: &mut W,
+    chunk_type: u32,
+    data: &[u8],
+    pad: u8,
+) -> Result<()> {
+    let padding = (4 - data.len() % 4) % 4;
+    let byte_length = (data.len() + padding) as u32;
+    file.write_all(&byte_length.to_le_bytes())?;
+    file.write_all(&chunk_type.to_le_bytes())?;
+    file.write_all(data)?;
+    file.write_all(&[pad; 3][0..padding])?;
+    Ok(())
+}
+
+//fp glb_save
+/// Save a Gltf as a GLB file, given the data for its buffers
+///
+/// If all_buffers is false then only buffer 0 is placed in the binary
+/// chunk of the GLB file (if there are any buffers); any other buffers must
+/// have a URI (such as a data: URI or a relative path) already set
+///
+/// If all_buffers is true then every buffer is placed in the binary chunk,
+/// each padded to a multiple of 4 bytes, and the buffer views are rebased
+/// onto the resulting single buffer
+pub fn glb_save<W, B>(file: &mut W, gltf: &Gltf, buffers: &[B], all_buffers: bool) -> Result<()>
+where
+    W: std::io::Write,
+    B: AsRef<[u8]>,
+{
+    let num_buffers = {
+        if all_buffers {
+            gltf.buffers().len()
+        } else {
+            gltf.buffers().len().min(1)
+        }
+    };
+    if buffers.len() < num_buffers {
+        return Err(Error::BufferRead);
+    }
+    let mut bin = vec![];
+    let mut buffer_bases = vec![];
+    for (gb, b) in gltf.buffers().iter().zip(buffers.iter()).take(num_buffers) {
+        let data = b.as_ref();
+        if data.len() < gb.byte_length() {
+            return Err(Error::BufferTooShort);
+        }
+        buffer_bases.push(bin.len());
+        bin.extend_from_slice(&data[0..gb.byte_length()]);
+        bin.resize(bin.len().next_multiple_of(4), 0);
+    }
+
+    let mut json_value = serde_json::to_value(gltf)?;
+    if num_buffers > 0 {
+        if let Some(JsonValue::Array(views)) = json_value.get_mut("bufferViews") {
+            for v in views.iter_mut() {
+                let buffer = v["buffer"].as_u64().unwrap_or(0) as usize;
+                if buffer >= num_buffers {
+                    continue;
+                }
+                let byte_offset = v["byteOffset"].as_u64().unwrap_or(0) as usize;
+                v["buffer"] = json!(0);
+                v["byteOffset"] = json!(buffer_bases[buffer] + byte_offset);
+            }
+        }
+        let byte_length = {
+            if all_buffers {
+                bin.len()
+            } else {
+                gltf.buffers()[0].byte_length()
+            }
+        };
+        if let Some(JsonValue::Array(gltf_buffers)) = json_value.get_mut("buffers") {
+            gltf_buffers.drain(0..num_buffers);
+            gltf_buffers.insert(0, json!({ "byteLength": byte_length }));
+        }
+    }
+    let json = serde_json::to_vec(&json_value)?;
+
+    let json_chunk_length = 8 + json.len().next_multiple_of(4);
+    let bin_chunk_length = {
+        if num_buffers > 0 {
+            8 + bin.len()
+        } else {
+            0
+        }
+    };
+    let byte_length = 12 + json_chunk_length + bin_chunk_length;
+    let Ok(byte_length) = u32::try_from(byte_length) else {
+        return Err(Error::GlbTooLarge);
+    };
+    file.write_all(&[0x67, 0x6c, 0x54, 0x46, 0x02, 0x00, 0x00, 0x00])?;
+    file.write_all(&byte_length.to_le_bytes())?;
+    write_chunk(file, 0x4E4F534A, &json, b' ')?;
+    if num_buffers > 0 {
+        write_chunk(file, 0x004E4942, &bin, 0)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//a Imports
+use futures_util::io::{AsyncRead, AsyncReadExt};
+
+use crate::glb::{map_io_error, parse_chunk_header, parse_glb_header};
+use crate::{Error, GlbChunk, GlbChunkType, Gltf, Result};
+
+//a AsyncGlbReader
+//tp AsyncGlbReader
+/// An asynchronous reader of a GLB file, given an [AsyncRead] positioned at
+/// the start of the file
+///
+/// The Json chunk is read and validated first with [Self::read_gltf], so
+/// that the [Gltf] is available before the binary chunk is read; the
+/// binary chunk (if there is one) is then found with [Self::bin_chunk],
+/// and its data streamed into buffers supplied by the caller with
+/// [Self::read_data] or [Self::read_data_exact]
+///
+/// The validation of the file and its chunks is the same as for
+/// [crate::GlbChunks]
+pub struct AsyncGlbReader<'file, F: AsyncRead + Unpin> {
+    /// The GLB file being read, positioned after the data that has been
+    /// consumed
+    file: &'file mut F,
+    /// The byte length of the file, from the GLB header
+    byte_length: usize,
+    /// The byte offset of the file that has been consumed (including the data
+    /// of the current chunk)
+    byte_offset: usize,
+    /// The number of bytes of the current chunk's data that have not been read
+    pending: usize,
+    /// The number of chunks whose headers have been read
+    num_chunks: usize,
+}
+
+//ip AsyncGlbReader
+impl<'file, F: AsyncRead + Unpin> AsyncGlbReader<'file, F> {
+    //cp new
+    /// Create a new reader by reading and validating the GLB header from
+    /// the file
+    pub async fn new(file: &'file mut F) -> Result<Self> {
+        let mut hdr = [0; 12];
+        file.read_exact(&mut hdr)
+            .await
+            .map_err(|e| map_io_error(e, Error::Io))?;
+        let byte_length = parse_glb_header(&hdr)?;
+        Ok(Self {
+            file,
+            byte_length,
+            byte_offset: 12,
+            pending: 0,
+            num_chunks: 0,
+        })
+    }
+
+    //ap byte_length
+    /// Get the byte length of the GLB file given by its header
+    pub fn byte_length(&self) -> usize {
+        self.byte_length
+    }
+
+    //ap pending
+    /// Get the number of bytes of the data of the current chunk that have
+    /// not been read
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    //mi skip_pending
+    /// Skip any data of the current chunk that has not been read
+    async fn skip_pending(&mut self) -> Result<()> {
+        let mut scratch = [0; 4096];
+        while self.pending > 0 {
+            let n = self.pending.min(scratch.len());
+            self.read_data_exact(&mut scratch[0..n]).await?;
+        }
+        Ok(())
+    }
+
+    //mp next_chunk
+    /// Read the header of the next chunk, if there is one, skipping any
+    /// data of the current chunk that has not been read
+    pub async fn next_chunk(&mut self) -> Result<Option<GlbChunk>> {
+        self.skip_pending().await?;
+        if self.byte_offset == self.byte_length {
+            return Ok(None);
+        }
+        if self.byte_offset + 8 > self.byte_length {
+            return Err(Error::GlbChunkLength);
+        }
+        let mut hdr = [0; 8];
+        self.file
+            .read_exact(&mut hdr)
+            .await
+            .map_err(|e| map_io_error(e, Error::Io))?;
+        let chunk = parse_chunk_header(&hdr, self.byte_offset, self.byte_length)?;
+        self.byte_offset += 8 + chunk.byte_length;
+        self.pending = chunk.byte_length;
+        self.num_chunks += 1;
+        Ok(Some(chunk))
+    }
+
+    //mp read_data
+    /// Read some of the remaining data of the current chunk into a buffer,
+    /// returning the number of bytes read; this is 0 only if the buffer is
+    /// empty or all of the data of the chunk has been read
+    pub async fn read_data(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let n = buffer.len().min(self.pending);
+        if n == 0 {
+            return Ok(0);
+        }
+        let n = self.file.read(&mut buffer[0..n]).await?;
+        if n == 0 {
+            return Err(Error::GlbTruncated);
+        }
+        self.pending -= n;
+        Ok(n)
+    }
+
+    //mp read_data_exact
+    /// Read exactly enough of the remaining data of the current chunk to
+    /// fill a buffer, which must be no longer than that data
+    pub async fn read_data_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+        if buffer.len() > self.pending {
+            return Err(Error::BufferTooShort);
+        }
+        self.file
+            .read_exact(buffer)
+            .await
+            .map_err(|e| map_io_error(e, Error::GlbBinIo))?;
+        self.pending -= buffer.len();
+        Ok(())
+    }
+
+    //mp read_gltf
+    /// Read the Json chunk of the GLB file, which must be the first chunk,
+    /// and validate the length of it, and parse and validate it as a
+    /// [Gltf]
+    pub async fn read_gltf(&mut self, max_json_length: usize) -> Result<Gltf> {
+        if self.num_chunks != 0 {
+            return Err(Error::GlbJsonHdr);
+        }
+        let chunk = self.next_chunk().await?.ok_or(Error::GlbJsonHdr)?;
+        if chunk.chunk_type != GlbChunkType::Json {
+            return Err(Error::GlbJsonHdr);
+        }
+        if chunk.byte_length > max_json_length {
+            return Err(Error::GlbJsonLength);
+        }
+        let mut buffer = vec![0; chunk.byte_length];
+        self.read_data_exact(&mut buffer)
+            .await
+            .map_err(|e| match e {
+                Error::GlbBinIo(e) => Error::GlbJsonIo(e),
+                e => e,
+            })?;
+        let json_value = serde_json::from_str(std::str::from_utf8(&buffer)?)?;
+        Gltf::of_json_value(json_value)
+    }
+
+    //mp bin_chunk
+    /// Read the header of the binary buffer chunk, which (if present) must
+    /// immediately follow the Json chunk, and return its byte length
+    ///
+    /// This must be invoked after [Self::read_gltf]; if it returns Some
+    /// then the data of the chunk may be read with [Self::read_data] or
+    /// [Self::read_data_exact]
+    pub async fn bin_chunk(&mut self) -> Result<Option<usize>> {
+        if self.num_chunks != 1 {
+            return Err(Error::GlbBinHdr);
+        }
+        match self.next_chunk().await? {
+            None => Ok(None),
+            Some(chunk) => match chunk.chunk_type {
+                GlbChunkType::Bin => Ok(Some(chunk.byte_length)),
+                GlbChunkType::Json => Err(Error::GlbJsonHdr),
+                GlbChunkType::Unknown(_) => Ok(None),
+            },
+        }
+    }
+}
+
+//fp glb_load_async
+/// Load a GLB file asynchronously, reading the binary buffer chunk (if any)
+/// into a Vec<u8>
+///
+/// Use an [AsyncGlbReader] directly to obtain the [Gltf] before the binary
+/// chunk is read, or to read the chunk into another buffer
+pub async fn glb_load_async<F>(
+    file: &mut F,
+    max_json_length: usize,
+) -> Result<(Gltf, Option<Vec<u8>>)>
+where
+    F: AsyncRead + Unpin,
+{
+    let mut reader = AsyncGlbReader::new(file).await?;
+    let gltf = reader.read_gltf(max_json_length).await?;
+    let Some(byte_length) = reader.bin_chunk().await? else {
+        return Ok((gltf, None));
+    };
+    let mut buffer = vec![0; byte_length];
+    reader.read_data_exact(&mut buffer).await?;
+    Ok((gltf, Some(buffer)))
+}
@@ -8,18 +8,19 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde_json")]
 use serde_json::Value as JsonValue;
 
-#[cfg(not(feature = "serde_json"))]
-pub type JsonValue = ();
-
+use crate::utils::{mat4_affine_inverse, mat4_of_transformation};
+use crate::{Aabb, BoundingSphere};
 use crate::{
-    AccessorIndex, BufferIndex, ImageIndex, Indexable, MaterialIndex, MeshIndex, NHIndex,
-    NodeIndex, SceneIndex, TextureIndex, ViewIndex,
+    AccessorIndex, AnimationIndex, BufferIndex, CameraIndex, ImageIndex, Indexable, MaterialIndex,
+    MeshIndex, NHIndex, NodeIndex, SamplerIndex, SceneIndex, SkinIndex, TextureIndex, ViewIndex,
 };
-use crate::{Error, Named, Result};
 use crate::{
-    GltfAccessor, GltfAsset, GltfBuffer, GltfBufferView, GltfImage, GltfMaterial, GltfMesh,
-    GltfNode, GltfScene, GltfTexture,
+    AlphaMode, GltfAccessor, GltfAccessorType, GltfAsset, GltfBuffer, GltfBufferView, GltfCamera,
+    GltfImage, GltfMaterial, GltfMesh, GltfNode, GltfSampler, GltfScene, GltfSkin, GltfSparse,
+    GltfTexture,
 };
+use crate::{AnimationPose, GltfAnimation};
+use crate::{Error, Named, Result, ValidationMode, ValidationReport};
 
 //a Gltf
 //tp Gltf
@@ -70,25 +71,31 @@ pub struct Gltf {
     /// rendered for a scene
     scenes: Vec<GltfScene>,
 
-    /// The camers in the gltf; currently unfilled
-    cameras: Vec<JsonValue>,
+    /// The cameras in the gltf; these are placed in a scene by nodes that
+    /// refer to them by CameraIndex
+    cameras: Vec<GltfCamera>,
 
     /// The image descriptors from the Json file; this is the URI or
     /// buffer views, not the underlying image data
     images: Vec<GltfImage>,
 
-    /// The sampler descriptors from the Json file
-    samplers: JsonValue,
+    /// The sampler descriptors from the Json file; these are referred to by
+    /// textures by SamplerIndex
+    samplers: Vec<GltfSampler>,
 
     /// The texture descriptors from the Json file; these refer to
     /// SamplerIndex and ImageIndex
     textures: Vec<GltfTexture>,
 
-    /// The skin (skeleton) descriptors from the Json file
-    skins: Vec<JsonValue>,
+    /// The skin (skeleton) descriptors from the Json file; these refer
+    /// to NodeIndex for the joints and an AccessorIndex for the inverse
+    /// bind matrices
+    skins: Vec<GltfSkin>,
 
-    /// The animations in the Json file
-    animations: JsonValue,
+    /// The animations in the Json file; each has channels that target
+    /// properties of nodes by NodeIndex, and samplers that refer to
+    /// accessors by AccessorIndex
+    animations: Vec<GltfAnimation>,
 
     /// The hierarchy of nodes
     ///
@@ -164,6 +171,46 @@ impl std::ops::Index<MaterialIndex> for Gltf {
     }
 }
 
+//ip Index<SkinIndex> for Gltf
+impl std::ops::Index<SkinIndex> for Gltf {
+    type Output = GltfSkin;
+    fn index(&self, index: SkinIndex) -> &Self::Output {
+        &self.skins[index.as_usize()]
+    }
+}
+
+//ip Index<AnimationIndex> for Gltf
+impl std::ops::Index<AnimationIndex> for Gltf {
+    type Output = GltfAnimation;
+    fn index(&self, index: AnimationIndex) -> &Self::Output {
+        &self.animations[index.as_usize()]
+    }
+}
+
+//ip Index<SamplerIndex> for Gltf
+impl std::ops::Index<SamplerIndex> for Gltf {
+    type Output = GltfSampler;
+    fn index(&self, index: SamplerIndex) -> &Self::Output {
+        &self.samplers[index.as_usize()]
+    }
+}
+
+//ip Index<CameraIndex> for Gltf
+impl std::ops::Index<CameraIndex> for Gltf {
+    type Output = GltfCamera;
+    fn index(&self, index: CameraIndex) -> &Self::Output {
+        &self.cameras[index.as_usize()]
+    }
+}
+
+//ip Index<SceneIndex> for Gltf
+impl std::ops::Index<SceneIndex> for Gltf {
+    type Output = GltfScene;
+    fn index(&self, index: SceneIndex) -> &Self::Output {
+        &self.scenes[index.as_usize()]
+    }
+}
+
 //ip Gltf
 impl Gltf {
     pub fn set_asset(&mut self, asset: GltfAsset) {
@@ -185,6 +232,26 @@ impl Gltf {
         self.nodes.push(node);
         n.into()
     }
+    pub fn add_skin(&mut self, skin: GltfSkin) -> SkinIndex {
+        let n = self.skins.len();
+        self.skins.push(skin);
+        n.into()
+    }
+    pub fn add_animation(&mut self, animation: GltfAnimation) -> AnimationIndex {
+        let n = self.animations.len();
+        self.animations.push(animation);
+        n.into()
+    }
+    pub fn add_sampler(&mut self, sampler: GltfSampler) -> SamplerIndex {
+        let n = self.samplers.len();
+        self.samplers.push(sampler);
+        n.into()
+    }
+    pub fn add_camera(&mut self, camera: GltfCamera) -> CameraIndex {
+        let n = self.cameras.len();
+        self.cameras.push(camera);
+        n.into()
+    }
     pub fn add_scene(&mut self, scene: GltfScene) -> SceneIndex {
         let n = self.scenes.len();
         self.scenes.push(scene);
@@ -209,126 +276,667 @@ impl Gltf {
         self.buffer_views.push(view);
         n.into()
     }
+    //mp add_accessor
+    /// Add an accessor of a buffer view to the Gltf, returning its index
+    ///
+    /// The accessor has no min and max; these are filled in by a later
+    /// [Self::compute_accessor_bounds], once the buffer data is available
     pub fn add_accessor(
         &mut self,
         buffer_view: ViewIndex,
         byte_offset: u32,
         count: u32,
         element_type: mod3d_base::BufferElementType,
-        elements_per_data: usize,
+        normalized: bool,
+        accessor_type: GltfAccessorType,
     ) -> AccessorIndex {
-        let acc = GltfAccessor::new(
+        let mut acc = GltfAccessor::new(
             buffer_view,
             byte_offset as usize,
             count as usize,
             element_type,
-            elements_per_data,
+            accessor_type,
         );
+        acc.set_normalized(normalized);
 
         let n = self.accessors.len();
         self.accessors.push(acc);
         n.into()
     }
     //mp validate_buffer_views
-    /// Validate the contents - check indices in range, etc
-    fn validate_buffer_views(&self) -> Result<()> {
+    /// Validate the buffer views - check indices in range, etc
+    fn validate_buffer_views(&self, report: &mut ValidationReport) {
         let n = self.buffers.len();
         for (i, bv) in self.buffer_views.iter().enumerate() {
             let b = bv.buffer();
             if b.as_usize() >= n {
-                return Err(Error::BadJson(format!(
-                    "Buffer view index {i} has buffer {b} out of range (must be < {n})",
-                )));
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    format!("/bufferViews/{i}/buffer"),
+                    format!("Buffer {b} out of range (must be < {n})"),
+                );
+                continue;
             }
             let l = self.buffers[b.as_usize()].byte_length();
             if bv.byte_end() > l {
-                return Err(Error::BadJson(format!(
-                    "Buffer view index {i} specifies subrange outside the buffer size {l})",
-                )));
+                report.error(
+                    "BUFFER_VIEW_TOO_LONG",
+                    format!("/bufferViews/{i}/byteLength"),
+                    format!("Buffer view specifies subrange outside the buffer size {l}"),
+                );
             }
         }
-        Ok(())
     }
 
     //mp validate_accessors
-    /// Validate the contents - check indices in range, etc
-    fn validate_accessors(&self) -> Result<()> {
+    /// Validate the accessors - check indices in range, etc
+    fn validate_accessors(&self, report: &mut ValidationReport) {
         let n = self.buffer_views.len();
-        for acc in &self.accessors {
-            let bv_index = acc.buffer_view();
-            let Some(bv_index) = bv_index else {
-                return Err(Error::BadJson(
-                    "Accessor is not permitted to not specify a BufferView in this GLTF reader"
-                        .into(),
-                ));
+        for (i, acc) in self.accessors.iter().enumerate() {
+            use mod3d_base::BufferElementType::*;
+            if acc.normalized() && matches!(acc.component_type(), Float32 | Int32 | UInt32) {
+                report.error(
+                    "ACCESSOR_NORMALIZED_INVALID",
+                    format!("/accessors/{i}/normalized"),
+                    format!(
+                        "Accessor with component type {0:?} must not be normalized",
+                        acc.component_type()
+                    ),
+                );
+            }
+            if acc.count() == 0 {
+                report.error(
+                    "VALUE_NOT_IN_RANGE",
+                    format!("/accessors/{i}/count"),
+                    "Accessor count must be at least 1",
+                );
+            }
+            let num_components = acc.elements_per_data();
+            for (property, values) in [("min", acc.min()), ("max", acc.max())] {
+                if !values.is_empty() && values.len() != num_components {
+                    report.error(
+                        "ARRAY_LENGTH_NOT_IN_LIST",
+                        format!("/accessors/{i}/{property}"),
+                        format!(
+                            "Accessor {property} has {0} values but type {1} has {num_components} components",
+                            values.len(),
+                            acc.accessor_type()
+                        ),
+                    );
+                }
+            }
+            if let Some(sparse) = acc.sparse() {
+                self.validate_sparse(i, acc, sparse, report);
+            }
+            let Some(bv_index) = acc.buffer_view() else {
+                continue;
             };
             if bv_index.as_usize() >= n {
-                return Err(Error::BadJson(format!(
-                    "Accessor's buffer view index {0} out of range (must be < {n})",
-                    bv_index
-                )));
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    format!("/accessors/{i}/bufferView"),
+                    format!("Buffer view index {bv_index} out of range (must be < {n})"),
+                );
+                continue;
             }
             let bv = &self.buffer_views[bv_index.as_usize()];
+            let component_byte_length = acc.component_type().byte_length();
+            if acc.byte_offset() % component_byte_length != 0 {
+                report.error(
+                    "ACCESSOR_OFFSET_ALIGNMENT",
+                    format!("/accessors/{i}/byteOffset"),
+                    format!(
+                        "Accessor byte offset must be a multiple of the component size {component_byte_length}"
+                    ),
+                );
+            } else if (bv.byte_offset() + acc.byte_offset()) % component_byte_length != 0 {
+                report.error(
+                    "ACCESSOR_TOTAL_OFFSET_ALIGNMENT",
+                    format!("/accessors/{i}/byteOffset"),
+                    format!(
+                        "Accessor offset within the buffer must be a multiple of the component size {component_byte_length}"
+                    ),
+                );
+            }
+            let view_byte_stride = bv.byte_stride(0);
+            if view_byte_stride != 0 && view_byte_stride < acc.ele_byte_size() {
+                report.error(
+                    "ACCESSOR_SMALL_BYTESTRIDE",
+                    format!("/accessors/{i}"),
+                    format!(
+                        "Buffer view {bv_index} byte stride {view_byte_stride} is less than the {0} bytes of an element of type {1}",
+                        acc.ele_byte_size(),
+                        acc.accessor_type()
+                    ),
+                );
+            }
             let acc_byte_end = acc.byte_view_end(bv.byte_stride(0));
             if acc_byte_end > bv.byte_length() {
-                return Err(Error::BadJson(format!(
-                    "Accessor's last element ends (@{0}) beyond end of buffer view index {1} (at {2})",
-                    acc_byte_end,
-                    bv.buffer(),
-                    bv.byte_length()
-                )));
+                report.error(
+                    "ACCESSOR_TOO_LONG",
+                    format!("/accessors/{i}"),
+                    format!(
+                        "Accessor's last element ends (@{0}) beyond end of buffer view index {bv_index} (at {1})",
+                        acc_byte_end,
+                        bv.byte_length()
+                    ),
+                );
+            }
+        }
+    }
+
+    //mi validate_sparse
+    /// Validate the sparse storage of an accessor
+    fn validate_sparse(
+        &self,
+        i: usize,
+        acc: &GltfAccessor,
+        sparse: &GltfSparse,
+        report: &mut ValidationReport,
+    ) {
+        if sparse.count == 0 || sparse.count > acc.count() {
+            report.error(
+                "VALUE_NOT_IN_RANGE",
+                format!("/accessors/{i}/sparse/count"),
+                format!(
+                    "Sparse accessor count {0} must be at least 1 and no more than the accessor count {1}",
+                    sparse.count,
+                    acc.count()
+                ),
+            );
+        }
+        use mod3d_base::BufferElementType::*;
+        if !matches!(sparse.indices.component_type, UInt8 | UInt16 | UInt32) {
+            report.error(
+                "VALUE_NOT_IN_LIST",
+                format!("/accessors/{i}/sparse/indices/componentType"),
+                "Sparse accessor indices must be 8-, 16- or 32-bit unsigned integers",
+            );
+        }
+        let ranges = [
+            (
+                "indices",
+                sparse.indices.buffer_view,
+                sparse.indices.byte_offset + sparse.indices_byte_length(),
+            ),
+            (
+                "values",
+                sparse.values.buffer_view,
+                sparse.values.byte_offset + sparse.count * acc.ele_byte_size(),
+            ),
+        ];
+        for (property, bv, byte_end) in ranges {
+            let Some(view) = self.buffer_views.get(bv.as_usize()) else {
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    format!("/accessors/{i}/sparse/{property}/bufferView"),
+                    format!("Sparse accessor buffer view index {bv} out of range"),
+                );
+                continue;
+            };
+            if byte_end > view.byte_length() {
+                report.error(
+                    "ACCESSOR_SPARSE_TOO_LONG",
+                    format!("/accessors/{i}/sparse/{property}"),
+                    format!(
+                        "Sparse accessor data ends (@{byte_end}) beyond end of buffer view index {bv}"
+                    ),
+                );
             }
         }
-        Ok(())
+    }
+
+    //mi node_parents
+    /// Get the parent of each node from the children of the nodes, ignoring
+    /// children that are out of range; if a node is the child of more than
+    /// one node then the first is its parent
+    fn node_parents(&self) -> Vec<Option<NodeIndex>> {
+        let l = self.nodes.len();
+        let mut parents = vec![None; l];
+        for (i, n) in self.nodes.iter().enumerate() {
+            for c in n.iter_children() {
+                if c.as_usize() < l && parents[c.as_usize()].is_none() {
+                    parents[c.as_usize()] = Some(i.into());
+                }
+            }
+        }
+        parents
     }
 
     //mp validate_nodes
-    pub fn validate_nodes(&self) -> Result<()> {
+    /// Validate the nodes - check indices in range, that they form trees,
+    /// etc
+    pub fn validate_nodes(&self, report: &mut ValidationReport) {
         let l = self.nodes.len();
+        let parents = self.node_parents();
         for (i, n) in self.nodes.iter().enumerate() {
             for c in n.iter_children() {
                 if c.as_usize() >= l {
-                    return Err(Error::BadJson(format!(
-                        "Node {i} has child index {c} out of range",
-                    )));
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/nodes/{i}/children"),
+                        format!("Child index {c} out of range"),
+                    );
+                } else if parents[c.as_usize()] != Some(i.into()) {
+                    report.error(
+                        "NODE_PARENT_OVERRIDE",
+                        format!("/nodes/{i}/children"),
+                        format!("Child {c} is already the child of another node"),
+                    );
+                }
+            }
+            let mut p = parents[i];
+            for _ in 0..l {
+                let Some(pn) = p else {
+                    break;
+                };
+                if pn.as_usize() == i {
+                    report.error(
+                        "NODE_LOOP",
+                        format!("/nodes/{i}"),
+                        "Node is its own ancestor",
+                    );
+                    break;
                 }
+                p = parents[pn.as_usize()];
             }
             if let Some(m) = n.mesh() {
-                if m.as_usize() > self.meshes.len() {
-                    return Err(Error::BadJson(format!(
-                        "Node {i} has mesh index {m} out of range",
-                    )));
+                if m.as_usize() >= self.meshes.len() {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/nodes/{i}/mesh"),
+                        format!("Mesh index {m} out of range"),
+                    );
                 }
             }
             if let Some(c) = n.camera() {
-                if c.as_usize() > self.cameras.len() {
-                    return Err(Error::BadJson(format!(
-                        "Node {i} has camera index {c} out of range",
-                    )));
+                if c.as_usize() >= self.cameras.len() {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/nodes/{i}/camera"),
+                        format!("Camera index {c} out of range"),
+                    );
                 }
             }
             if let Some(s) = n.skin() {
-                if s.as_usize() > self.skins.len() {
-                    return Err(Error::BadJson(format!(
-                        "Node {i} has skin index {s} out of range",
-                    )));
+                if s.as_usize() >= self.skins.len() {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/nodes/{i}/skin"),
+                        format!("Skin index {s} out of range"),
+                    );
+                }
+                if n.mesh().is_some() && parents[i].is_some() {
+                    report.warning(
+                        "NODE_SKINNED_MESH_NON_ROOT",
+                        format!("/nodes/{i}"),
+                        "Node has a skinned mesh, which its parents' transforms will not affect",
+                    );
                 }
             }
-            n.validate(i.into())?;
+            if let (Some(m), Some(w)) = (n.mesh(), n.weights()) {
+                let num_targets = self.meshes.get(m.as_usize()).map_or(0, |m| m.num_targets());
+                if w.len() != num_targets {
+                    report.error(
+                        "NODE_WEIGHTS_INVALID",
+                        format!("/nodes/{i}/weights"),
+                        format!(
+                            "Node has {0} morph target weights but its mesh has {num_targets} targets",
+                            w.len()
+                        ),
+                    );
+                }
+            }
+            n.validate(i.into(), report);
+        }
+    }
+
+    //mp validate_meshes
+    /// Validate the meshes, and note any that are not used by a node
+    pub fn validate_meshes(&self, report: &mut ValidationReport) {
+        for (i, m) in self.meshes.iter().enumerate() {
+            m.validate(i.into(), self.accessors.len(), self.materials.len(), report);
+            if !self.nodes.iter().any(|n| n.mesh() == Some(i.into())) {
+                report.info(
+                    "UNUSED_OBJECT",
+                    format!("/meshes/{i}"),
+                    "Mesh is not used by any node",
+                );
+            }
+        }
+    }
+
+    //mp validate_skins
+    /// Validate the skins - check the joints, skeleton and inverse bind
+    /// matrices are in range
+    pub fn validate_skins(&self, report: &mut ValidationReport) {
+        let l = self.nodes.len();
+        for (i, s) in self.skins.iter().enumerate() {
+            if s.joints().is_empty() {
+                report.error(
+                    "ARRAY_LENGTH_NOT_IN_LIST",
+                    format!("/skins/{i}/joints"),
+                    "Skin has no joints",
+                );
+            }
+            for j in s.joints() {
+                if j.as_usize() >= l {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/skins/{i}/joints"),
+                        format!("Joint index {j} out of range"),
+                    );
+                }
+            }
+            if let Some(r) = s.skeleton() {
+                if r.as_usize() >= l {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/skins/{i}/skeleton"),
+                        format!("Skeleton index {r} out of range"),
+                    );
+                }
+            }
+            if let Some(a) = s.inverse_bind_matrices() {
+                let pointer = format!("/skins/{i}/inverseBindMatrices");
+                let Some(acc) = self.accessors.get(a.as_usize()) else {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        pointer,
+                        format!("Inverse bind matrices accessor {a} out of range"),
+                    );
+                    continue;
+                };
+                if acc.accessor_type() != GltfAccessorType::Mat4 || acc.count() < s.joints().len() {
+                    report.error(
+                        "SKIN_IBM_INVALID",
+                        pointer,
+                        format!("Inverse bind matrices accessor {a} must have a MAT4 per joint"),
+                    );
+                }
+            }
+        }
+    }
+
+    //mp validate_skeletons
+    /// Validate that the joints of each skin have a common ancestor in the
+    /// node hierarchy, and that the skeleton (if specified) is such an
+    /// ancestor
+    ///
+    /// This must be invoked after the node hierarchy has been generated
+    pub fn validate_skeletons(&self, report: &mut ValidationReport) {
+        for (i, s) in self.skins.iter().enumerate() {
+            let mut root = None;
+            for j in s.joints() {
+                let r = self.node_root(*j);
+                if root.is_some_and(|root| root != r) {
+                    report.error(
+                        "SKIN_NO_COMMON_ROOT",
+                        format!("/skins/{i}/joints"),
+                        "Skin has joints that do not share a common ancestor",
+                    );
+                    break;
+                }
+                root = Some(r);
+            }
+            if let Some(skeleton) = s.skeleton() {
+                for j in s.joints() {
+                    if !self.is_ancestor(skeleton, *j) {
+                        report.error(
+                            "SKIN_SKELETON_INVALID",
+                            format!("/skins/{i}/skeleton"),
+                            format!("Skeleton {skeleton} is not an ancestor of joint {j}"),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    //mp validate_materials
+    /// Validate the materials - check the alpha cutoff, factors and
+    /// emissive strength, and that the textures are in range
+    pub fn validate_materials(&self, report: &mut ValidationReport) {
+        let l = self.textures.len();
+        for (i, m) in self.materials.iter().enumerate() {
+            if let Some(alpha_cutoff) = m.alpha_cutoff {
+                if alpha_cutoff < 0.0 {
+                    report.error(
+                        "VALUE_NOT_IN_RANGE",
+                        format!("/materials/{i}/alphaCutoff"),
+                        "Alpha cutoff must not be negative",
+                    );
+                } else if m.alpha_mode() != AlphaMode::Mask {
+                    report.warning(
+                        "MATERIAL_ALPHA_CUTOFF_INVALID_MODE",
+                        format!("/materials/{i}/alphaCutoff"),
+                        "Alpha cutoff is only used with the MASK alpha mode",
+                    );
+                }
+            }
+            let base_color_factor = m
+                .pbr_metallic_roughness()
+                .as_ref()
+                .and_then(|pbr| pbr.base_color_factor.as_ref());
+            if let Some(c) = base_color_factor {
+                if c.len() != 4 {
+                    report.error(
+                        "ARRAY_LENGTH_NOT_IN_LIST",
+                        format!("/materials/{i}/pbrMetallicRoughness/baseColorFactor"),
+                        format!("Base color factor has {0} values, not 4", c.len()),
+                    );
+                }
+            }
+            if m.emissive_strength() < 0.0 {
+                report.error(
+                    "VALUE_NOT_IN_RANGE",
+                    format!(
+                        "/materials/{i}/extensions/KHR_materials_emissive_strength/emissiveStrength"
+                    ),
+                    "Emissive strength must not be negative",
+                );
+            }
+            let pbr = m.pbr_metallic_roughness().as_ref();
+            let texture_infos = [
+                ("normalTexture", m.normal_texture().as_ref()),
+                ("occlusionTexture", m.occlusion_texture().as_ref()),
+                ("emissiveTexture", m.emissive_texture().as_ref()),
+                (
+                    "pbrMetallicRoughness/baseColorTexture",
+                    pbr.and_then(|p| p.base_color_texture().as_ref()),
+                ),
+                (
+                    "pbrMetallicRoughness/metallicRoughnessTexture",
+                    pbr.and_then(|p| p.metallic_roughness_texture().as_ref()),
+                ),
+            ];
+            for (property, ti) in texture_infos {
+                let Some(ti) = ti else {
+                    continue;
+                };
+                if ti.index().as_usize() >= l {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/materials/{i}/{property}/index"),
+                        format!("Texture index {0} out of range", ti.index()),
+                    );
+                }
+            }
+        }
+    }
+
+    //mp validate_textures
+    /// Validate the textures - check the images and samplers are in range
+    pub fn validate_textures(&self, report: &mut ValidationReport) {
+        for (i, t) in self.textures.iter().enumerate() {
+            if t.image().as_usize() >= self.images.len() {
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    format!("/textures/{i}/source"),
+                    format!("Image index {0} out of range", t.image()),
+                );
+            }
+            if let Some(s) = t.sampler() {
+                if s.as_usize() >= self.samplers.len() {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/textures/{i}/sampler"),
+                        format!("Sampler index {s} out of range"),
+                    );
+                }
+            }
+        }
+    }
+
+    //mp validate_images
+    /// Validate the images - check they have a URI or an in-range buffer
+    /// view (with a mime type)
+    pub fn validate_images(&self, report: &mut ValidationReport) {
+        for (i, image) in self.images.iter().enumerate() {
+            match (image.uri(), image.buffer_view()) {
+                (Some(_), Some(_)) | (None, None) => {
+                    report.error(
+                        "ONE_OF_MISMATCH",
+                        format!("/images/{i}"),
+                        "Image must have exactly one of a URI and a buffer view",
+                    );
+                }
+                (None, Some(bv)) => {
+                    if bv.as_usize() >= self.buffer_views.len() {
+                        report.error(
+                            "UNRESOLVED_REFERENCE",
+                            format!("/images/{i}/bufferView"),
+                            format!("Buffer view index {bv} out of range"),
+                        );
+                    }
+                    if image.mime_type().is_empty() {
+                        report.error(
+                            "UNSATISFIED_DEPENDENCY",
+                            format!("/images/{i}/mimeType"),
+                            "Image with a buffer view must have a mime type",
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    //mp validate_scenes
+    /// Validate the scenes - check their nodes are in range and are root
+    /// nodes, and that the default scene is in range
+    pub fn validate_scenes(&self, report: &mut ValidationReport) {
+        let parents = self.node_parents();
+        for (i, s) in self.scenes.iter().enumerate() {
+            for n in &s.nodes {
+                if n.as_usize() >= self.nodes.len() {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/scenes/{i}/nodes"),
+                        format!("Node index {n} out of range"),
+                    );
+                } else if parents[n.as_usize()].is_some() {
+                    report.error(
+                        "SCENE_NON_ROOT_NODE",
+                        format!("/scenes/{i}/nodes"),
+                        format!("Node {n} is not a root node"),
+                    );
+                }
+            }
+        }
+        if let Some(s) = self.scene {
+            if s.as_usize() >= self.scenes.len() {
+                report.error(
+                    "UNRESOLVED_REFERENCE",
+                    "/scene",
+                    format!("Scene index {s} out of range"),
+                );
+            }
+        }
+    }
+
+    //mp validate_animations
+    /// Validate the animations - check the channels, nodes and accessors are
+    /// in range
+    pub fn validate_animations(&self, report: &mut ValidationReport) {
+        for (i, a) in self.animations.iter().enumerate() {
+            a.validate(i.into(), self.nodes.len(), self.accessors.len(), report);
+        }
+    }
+
+    //mp validate_cameras
+    /// Validate the cameras
+    pub fn validate_cameras(&self, report: &mut ValidationReport) {
+        for (i, c) in self.cameras.iter().enumerate() {
+            c.validate(i.into(), report);
         }
-        Ok(())
+    }
+
+    //mp validation_report
+    /// Validate the contents - check indices in range, etc - and return a
+    /// report of all the issues found
+    ///
+    /// This does not include the validation of the skeletons, which
+    /// requires the node hierarchy
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        self.validate_buffer_views(&mut report);
+        self.validate_accessors(&mut report);
+        self.validate_meshes(&mut report);
+        self.validate_nodes(&mut report);
+        self.validate_scenes(&mut report);
+        self.validate_materials(&mut report);
+        self.validate_textures(&mut report);
+        self.validate_images(&mut report);
+        self.validate_skins(&mut report);
+        self.validate_animations(&mut report);
+        self.validate_cameras(&mut report);
+        report
     }
 
     //mp validate
     /// Validate the contents - check indices in range, etc
+    ///
+    /// Returns Err(Error::Validation) if there are any errors
     pub fn validate(&self) -> Result<()> {
-        dbg!(self);
-        self.validate_buffer_views()?;
-        self.validate_accessors()?;
-        self.validate_nodes()?;
+        self.validation_report()
+            .into_result(ValidationMode::Lenient)?;
         Ok(())
     }
 
+    //ap node_root
+    /// Get the root of the node hierarchy that contains a node
+    ///
+    /// This is only valid after the node hierarchy has been generated
+    pub fn node_root(&self, node: NodeIndex) -> NodeIndex {
+        let mut n = node;
+        for _ in 0..self.nodes.len() {
+            let Some(p) = self[n].parent() else {
+                break;
+            };
+            n = p;
+        }
+        n
+    }
+
+    //ap is_ancestor
+    /// Return true if a node is an ancestor of (or the same as) another node
+    ///
+    /// This is only valid after the node hierarchy has been generated
+    pub fn is_ancestor(&self, ancestor: NodeIndex, node: NodeIndex) -> bool {
+        let mut n = node;
+        for _ in 0..self.nodes.len() {
+            if n == ancestor {
+                return true;
+            }
+            let Some(p) = self[n].parent() else {
+                break;
+            };
+            n = p;
+        }
+        false
+    }
+
     //ap buffers
     pub fn buffers(&self) -> &[GltfBuffer] {
         &self.buffers
@@ -375,21 +983,376 @@ impl Gltf {
         &self.meshes
     }
 
+    //ap skins
+    pub fn skins(&self) -> &[GltfSkin] {
+        &self.skins
+    }
+
+    //ap get_skin
+    /// Get the [SkinIndex] of a named skin, or a skin by its usize index (as a
+    /// string)
+    ///
+    /// If the skin is not found then return None
+    pub fn get_skin(&self, name: &str) -> Option<SkinIndex> {
+        GltfSkin::get_named(self.skins(), name)
+    }
+
+    //ap animations
+    pub fn animations(&self) -> &[GltfAnimation] {
+        &self.animations
+    }
+
+    //ap get_animation
+    /// Get the [AnimationIndex] of a named animation, or an animation by its
+    /// usize index (as a string)
+    ///
+    /// If the animation is not found then return None
+    pub fn get_animation(&self, name: &str) -> Option<AnimationIndex> {
+        GltfAnimation::get_named(self.animations(), name)
+    }
+
+    //ap samplers
+    pub fn samplers(&self) -> &[GltfSampler] {
+        &self.samplers
+    }
+
+    //ap images
+    pub fn images(&self) -> &[GltfImage] {
+        &self.images
+    }
+
+    //ap textures
+    pub fn textures(&self) -> &[GltfTexture] {
+        &self.textures
+    }
+
+    //ap materials
+    pub fn materials(&self) -> &[GltfMaterial] {
+        &self.materials
+    }
+
+    //ap cameras
+    pub fn cameras(&self) -> &[GltfCamera] {
+        &self.cameras
+    }
+
+    //ap scenes
+    pub fn scenes(&self) -> &[GltfScene] {
+        &self.scenes
+    }
+
+    //ap scene
+    /// Get the default scene of the Gltf, if it specifies one
+    pub fn scene(&self) -> Option<SceneIndex> {
+        self.scene
+    }
+
+    //ap get_scene
+    /// Get the [SceneIndex] of a named scene, or a scene by its usize index
+    /// (as a string)
+    ///
+    /// If the scene is not found then return None
+    pub fn get_scene(&self, name: &str) -> Option<SceneIndex> {
+        GltfScene::get_named(self.scenes(), name)
+    }
+
+    //ap node_morph_weights
+    /// Get the morph target weights to use for the mesh of a node - those of
+    /// the node if it has them (or has been posed with them), else those of
+    /// its mesh
+    ///
+    /// If the node has no mesh, or the mesh has no morph targets, then None
+    /// is returned
+    pub fn node_morph_weights(&self, node: NodeIndex) -> Option<&[f32]> {
+        let n = &self[node];
+        let mesh = &self[n.mesh()?];
+        if mesh.num_targets() == 0 {
+            None
+        } else if let Some(w) = n.weights() {
+            Some(w)
+        } else if !mesh.weights().is_empty() {
+            Some(mesh.weights())
+        } else {
+            None
+        }
+    }
+
+    //ap mesh_aabb
+    /// Get the axis-aligned bounding box of a mesh in its own coordinates,
+    /// from the min and max of the POSITION accessors of its primitives
+    ///
+    /// The POSITION displacements of any morph targets are included, so the
+    /// box contains the mesh for all weights in the range 0 to 1
+    ///
+    /// If any POSITION accessor does not have bounds then None is returned
+    pub fn mesh_aabb(&self, mesh: MeshIndex) -> Option<Aabb> {
+        let position = |attrs: &[(mod3d_base::VertexAttr, AccessorIndex)]| {
+            attrs
+                .iter()
+                .find(|(va, _)| *va == mod3d_base::VertexAttr::Position)
+                .map(|(_, a)| *a)
+        };
+        let mut result: Option<Aabb> = None;
+        for p in self[mesh].primitives() {
+            let Some(a) = position(p.attributes()) else {
+                continue;
+            };
+            let mut aabb = Aabb::of_min_max(self[a].min(), self[a].max())?;
+            for t in p.targets() {
+                let Some(a) = position(t) else {
+                    continue;
+                };
+                let t = Aabb::of_min_max(self[a].min(), self[a].max())?;
+                aabb.extend(&t.min.map(|x| x.min(0.)), &t.max.map(|x| x.max(0.)));
+            }
+            result = Some(result.map_or(aabb, |r| r.union(&aabb)));
+        }
+        result
+    }
+
+    //ap node_aabb
+    /// Get the axis-aligned bounding box of the mesh of a node in world
+    /// coordinates, using its global transformation
+    pub fn node_aabb(&self, node: NodeIndex) -> Option<Aabb> {
+        let n = &self[node];
+        let aabb = self.mesh_aabb(n.mesh()?)?;
+        Some(aabb.transformed(&mat4_of_transformation(n.global_transformation())))
+    }
+
+    //ap scene_aabb
+    /// Get the axis-aligned bounding box (in world coordinates) of all the
+    /// meshes of the nodes of a scene
+    pub fn scene_aabb(&self, scene: SceneIndex) -> Option<Aabb> {
+        self.scene_nodes(scene)
+            .into_iter()
+            .filter_map(|n| self.node_aabb(n))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    //ap mesh_bounding_sphere
+    /// Get a bounding sphere for a mesh in its own coordinates
+    pub fn mesh_bounding_sphere(&self, mesh: MeshIndex) -> Option<BoundingSphere> {
+        self.mesh_aabb(mesh).map(|a| a.bounding_sphere())
+    }
+
+    //ap node_bounding_sphere
+    /// Get a bounding sphere for the mesh of a node in world coordinates
+    pub fn node_bounding_sphere(&self, node: NodeIndex) -> Option<BoundingSphere> {
+        self.node_aabb(node).map(|a| a.bounding_sphere())
+    }
+
+    //ap scene_bounding_sphere
+    /// Get a bounding sphere for all the meshes of a scene in world
+    /// coordinates
+    pub fn scene_bounding_sphere(&self, scene: SceneIndex) -> Option<BoundingSphere> {
+        self.scene_aabb(scene).map(|a| a.bounding_sphere())
+    }
+
+    //mp scene_nodes
+    /// Get all the nodes in a scene - the root nodes of the scene and all of
+    /// their descendants, in depth-first order
+    pub fn scene_nodes(&self, scene: SceneIndex) -> Vec<NodeIndex> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut result = vec![];
+        let mut stack: Vec<NodeIndex> = self[scene].nodes.iter().rev().copied().collect();
+        while let Some(n) = stack.pop() {
+            if visited[n.as_usize()] {
+                continue;
+            }
+            visited[n.as_usize()] = true;
+            result.push(n);
+            stack.extend(self[n].iter_children().rev());
+        }
+        result
+    }
+
+    //mp camera_nodes
+    /// Get the nodes in a scene that are cameras, with the camera and the
+    /// global transformation of each node
+    pub fn camera_nodes(
+        &self,
+        scene: SceneIndex,
+    ) -> Vec<(NodeIndex, CameraIndex, &Transformation)> {
+        self.scene_nodes(scene)
+            .into_iter()
+            .filter_map(|n| {
+                let node = &self[n];
+                node.camera().map(|c| (n, c, node.global_transformation()))
+            })
+            .collect()
+    }
+
+    //mp camera_view
+    /// Get the column-major view matrix for a camera node - the inverse of
+    /// its global transformation, mapping scene coordinates to camera
+    /// coordinates
+    pub fn camera_view(&self, node: NodeIndex) -> [f32; 16] {
+        mat4_affine_inverse(&mat4_of_transformation(self[node].global_transformation()))
+    }
+
+    //mi view_data
+    /// Get the data of a buffer view, given a function that provides the
+    /// data for a buffer (if it is available)
+    fn view_data<'a, F>(&self, view: ViewIndex, buffer: &F) -> Result<&'a [u8]>
+    where
+        F: Fn(BufferIndex) -> Option<&'a [u8]>,
+    {
+        let bv = &self[view];
+        let Some(data) = buffer(bv.buffer()) else {
+            return Err(Error::BufferRead);
+        };
+        data.get(bv.byte_offset()..bv.byte_end())
+            .ok_or(Error::BufferTooShort)
+    }
+
+    //mp accessor_f32s
+    /// Read the contents of an accessor as a Vec of f32, given a function
+    /// that provides the data for a buffer (if it is available)
+    pub fn accessor_f32s<'a, F>(&self, accessor: AccessorIndex, buffer: &F) -> Result<Vec<f32>>
+    where
+        F: Fn(BufferIndex) -> Option<&'a [u8]>,
+    {
+        self.accessor_values(accessor, buffer, false)
+    }
+
+    //mp accessor_raw_f32s
+    /// Read the contents of an accessor as a Vec of f32 of the raw
+    /// component values (without normalization, even if the accessor is
+    /// normalized), given a function that provides the data for a buffer
+    /// (if it is available)
+    pub fn accessor_raw_f32s<'a, F>(&self, accessor: AccessorIndex, buffer: &F) -> Result<Vec<f32>>
+    where
+        F: Fn(BufferIndex) -> Option<&'a [u8]>,
+    {
+        self.accessor_values(accessor, buffer, true)
+    }
+
+    //mi accessor_values
+    /// Read the contents of an accessor as a Vec of f32, either as the
+    /// raw component values or normalized (if the accessor is normalized)
+    fn accessor_values<'a, F>(
+        &self,
+        accessor: AccessorIndex,
+        buffer: &F,
+        raw: bool,
+    ) -> Result<Vec<f32>>
+    where
+        F: Fn(BufferIndex) -> Option<&'a [u8]>,
+    {
+        let acc = &self[accessor];
+        let read = |data: &[u8], byte_start, byte_stride| {
+            if raw {
+                acc.read_raw_f32s_of_data(data, byte_start, byte_stride)
+            } else {
+                acc.read_f32s_of_data(data, byte_start, byte_stride)
+            }
+        };
+        if acc.is_synthetic() {
+            let data = self.accessor_bytes(accessor, buffer)?;
+            return read(&data, 0, acc.ele_byte_size());
+        }
+        let bv = &self[acc.buffer_view().unwrap()];
+        let Some(data) = buffer(bv.buffer()) else {
+            return Err(Error::BufferRead);
+        };
+        read(
+            data,
+            bv.byte_offset() + acc.byte_offset(),
+            acc.byte_stride(bv.byte_stride(0)),
+        )
+    }
+
+    //mp compute_accessor_bounds
+    /// Compute the min and max of every accessor that does not have them,
+    /// given a function that provides the data for a buffer (if it is
+    /// available)
+    ///
+    /// Accessors whose buffers are not available are left without bounds
+    ///
+    /// The bounds are raw component values, as required by glTF, even
+    /// for normalized accessors
+    pub fn compute_accessor_bounds<'a, F>(&mut self, buffer: &F) -> Result<()>
+    where
+        F: Fn(BufferIndex) -> Option<&'a [u8]>,
+    {
+        for i in 0..self.accessors.len() {
+            if self.accessors[i].has_bounds() {
+                continue;
+            }
+            match self.accessor_raw_f32s(i.into(), buffer) {
+                Ok(values) => self.accessors[i].set_bounds(&values),
+                Err(Error::BufferRead) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    //mp accessor_bytes
+    /// Materialise the contents of an accessor as tightly packed bytes,
+    /// given a function that provides the data for a buffer (if it is
+    /// available)
+    ///
+    /// The contents are those of the buffer view of the accessor (or zeros
+    /// if it has none) with any sparse values overlaid
+    pub fn accessor_bytes<'a, F>(&self, accessor: AccessorIndex, buffer: &F) -> Result<Vec<u8>>
+    where
+        F: Fn(BufferIndex) -> Option<&'a [u8]>,
+    {
+        let acc = &self[accessor];
+        let ele_byte_size = acc.ele_byte_size();
+        let mut data = {
+            if let Some(bv) = acc.buffer_view() {
+                let view_data = self.view_data(bv, buffer)?;
+                let byte_stride = acc.byte_stride(self[bv].byte_stride(0));
+                acc.read_bytes(view_data, acc.byte_offset(), byte_stride)?
+            } else {
+                vec![0; acc.count() * ele_byte_size]
+            }
+        };
+        if let Some(sparse) = acc.sparse() {
+            let indices = self.view_data(sparse.indices.buffer_view, buffer)?;
+            let values = self.view_data(sparse.values.buffer_view, buffer)?;
+            sparse.overlay(&mut data, ele_byte_size, indices, values)?;
+        }
+        Ok(data)
+    }
+
     //ap nh_index
     pub fn nh_index(&self, node: NodeIndex) -> NHIndex {
         self.nh_index[node.as_usize()]
     }
 
     //cp of_json_value
-    /// Create a [GltfJsonValue] from a [serde::json::Value], doing
-    /// some validation
+    /// Create a [Gltf] from a [serde::json::Value], validating it
+    ///
+    /// Only errors cause it to be rejected; see [Self::of_json_value_with_mode]
     #[cfg(feature = "serde_json")]
     pub fn of_json_value(json_value: JsonValue) -> Result<Self> {
+        Ok(Self::of_json_value_with_mode(json_value, ValidationMode::Lenient)?.0)
+    }
+
+    //cp of_json_value_with_mode
+    /// Create a [Gltf] from a [serde::json::Value], validating it in a
+    /// [ValidationMode], and return it with the [ValidationReport]
+    ///
+    /// If the report does not permit the Gltf to be used in the mode then
+    /// Err(Error::Validation) is returned with the report
+    #[cfg(feature = "serde_json")]
+    pub fn of_json_value_with_mode(
+        json_value: JsonValue,
+        mode: ValidationMode,
+    ) -> Result<(Self, ValidationReport)> {
         let mut s: Self = serde_json::from_value(json_value)?;
-        s.validate()?;
+        let mut report = s.validation_report();
+        if report.has_errors() {
+            return Err(Error::Validation(report));
+        }
         s.gen_node_hierarchy();
+        s.validate_skeletons(&mut report);
+        let report = report.into_result(mode)?;
         s.derive();
-        Ok(s)
+        Ok((s, report))
     }
 
     //mp gen_node_hierarchy
@@ -404,11 +1367,16 @@ impl Gltf {
             let ni: NodeIndex = i.into();
             self.nh_index[i] = self.node_hierarchy.add_node(ni).into();
         }
+        let mut parents = vec![];
         for (i, n) in self.nodes.iter().enumerate() {
             for c in n.iter_children() {
                 self.node_hierarchy.relate(i, c.as_usize());
+                parents.push((*c, i.into()));
             }
         }
+        for (c, p) in parents {
+            self.nodes[c.as_usize()].set_parent(Some(p));
+        }
         self.node_hierarchy.find_roots();
     }
 
@@ -430,4 +1398,30 @@ impl Gltf {
             }
         }
     }
+
+    //mp set_pose
+    /// Set the pose of the nodes from an [AnimationPose], and derive the
+    /// global transformations of all the nodes
+    ///
+    /// Nodes that are not animated by the pose retain any previous pose
+    pub fn set_pose(&mut self, pose: &AnimationPose) {
+        for (n, t) in &pose.transformations {
+            self.nodes[n.as_usize()].set_pose(Some(*t));
+        }
+        for (n, w) in &pose.weights {
+            self.nodes[n.as_usize()].set_pose_weights(Some(w.clone()));
+        }
+        self.derive();
+    }
+
+    //mp clear_pose
+    /// Clear the pose of all the nodes, and derive the global
+    /// transformations from the Gltf node matrices or TRS
+    pub fn clear_pose(&mut self) {
+        for n in self.nodes.iter_mut() {
+            n.set_pose(None);
+            n.set_pose_weights(None);
+        }
+        self.derive();
+    }
 }
@@ -7,6 +7,10 @@ use crate::{ImageIndex, ViewIndex};
 
 //a GltfImage
 //tp GltfImage
+/// A type that contains the data from a Gltf Json 'Image'
+///
+/// The image data is provided by either a URI or a buffer view (in which
+/// case the mime type must be given)
 #[derive(Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
@@ -14,12 +18,21 @@ pub struct GltfImage {
     /// Optional name of the image
     pub name: String,
     /// Optional URI
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub uri: Option<String>,
-    /// Optional mime type ("image/jpeg" or "image/png")
-    #[cfg_attr(feature = "serde", serde(rename = "mimeType"))]
+    /// Optional mime type ("image/jpeg" or "image/png"); empty if not
+    /// specified
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "mimeType", skip_serializing_if = "String::is_empty")
+    )]
     pub mime_type: String,
-    #[cfg_attr(feature = "serde", serde(rename = "bufferView"))]
-    pub buffer_view: ViewIndex,
+    /// Optional buffer view containing the image data
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "bufferView", skip_serializing_if = "Option::is_none")
+    )]
+    pub buffer_view: Option<ViewIndex>,
 }
 
 //ip GltfImage
@@ -30,7 +43,7 @@ impl GltfImage {
     }
 
     //ap buffer_view
-    pub fn buffer_view(&self) -> ViewIndex {
+    pub fn buffer_view(&self) -> Option<ViewIndex> {
         self.buffer_view
     }
 
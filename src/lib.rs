@@ -87,40 +87,70 @@ pub use traits::Named;
 #[cfg(feature = "serde_json")]
 mod glb;
 #[cfg(feature = "serde_json")]
-pub use glb::glb_load;
+pub use glb::{glb_load, glb_load_slice, glb_save, GlbChunk, GlbChunkType, GlbChunks};
 
+#[cfg(feature = "async")]
+mod glb_async;
+#[cfg(feature = "async")]
+pub use glb_async::{glb_load_async, AsyncGlbReader};
+
+#[cfg(feature = "serde_json")]
+mod loader;
+#[cfg(feature = "serde_json")]
+pub use loader::GltfLoader;
+
+mod animation;
 mod asset;
+mod bounds;
 mod buffer_usage;
 mod buffers_accessors;
+mod camera;
 mod image;
 mod material;
 mod node;
 mod primitives_meshes;
+mod resolver;
+mod sampler;
 mod scene;
+mod skin;
 mod texture;
+mod validation;
 
 #[cfg(feature = "serde")]
 mod deserialize;
 #[cfg(feature = "serde")]
 mod serialize;
 
+pub use animation::{
+    AnimationEvaluator, AnimationPath, AnimationPose, GltfAnimation, GltfAnimationSampler,
+    GltfChannel, GltfChannelTarget, Interpolation,
+};
 pub use asset::GltfAsset;
+pub use bounds::{Aabb, BoundingSphere};
 pub(crate) use buffer_usage::BufferUsage;
-pub use buffers_accessors::{GltfAccessor, GltfBuffer, GltfBufferView};
+pub use buffers_accessors::{GltfAccessor, GltfAccessorType, GltfBuffer, GltfBufferView};
+pub use buffers_accessors::{GltfSparse, GltfSparseIndices, GltfSparseValues};
+pub use camera::{CameraType, GltfCamera, GltfOrthographic, GltfPerspective};
 pub use image::GltfImage;
-pub use material::GltfMaterial;
+pub use material::{AlphaMode, GltfEmissiveStrength, GltfMaterial, GltfMaterialExtensions};
 pub use node::GltfNode;
 pub use primitives_meshes::{GltfMesh, GltfPrimitive};
+pub use resolver::{DataUriResolver, DirResolver, MemoryResolver, ResolverChain, ResourceResolver};
+pub use sampler::{GltfSampler, MagFilter, MinFilter, Wrap};
 pub use scene::GltfScene;
+pub use skin::GltfSkin;
 pub use texture::{GltfTexture, GltfTextureInfo};
+pub use validation::{Severity, ValidationIssue, ValidationMode, ValidationReport};
 
 mod utils;
 pub use utils::{buf_parse_fail, try_buf_parse_base64};
+pub use utils::{image_mime_type, try_image_parse_base64};
+pub use utils::{percent_decode, uri_relative_path};
 
 mod gltf;
 pub use gltf::Gltf;
 
 mod object_data;
-pub use object_data::ObjectData;
+pub use object_data::{ODMaterial, ODMaterialTexture, ObjectData};
 mod od_use;
-pub(crate) use od_use::ODUses;
+pub(crate) use od_use::{ODUse, ODUses};
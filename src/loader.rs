@@ -0,0 +1,130 @@
+//a Imports
+use std::path::{Path, PathBuf};
+
+use crate::glb_load_slice;
+use crate::{DataUriResolver, DirResolver, ResolverChain, ResourceResolver};
+use crate::{Error, Gltf, Result};
+
+//a Constants
+/// The default maximum length of the Json chunk of a GLB file
+const DEFAULT_MAX_JSON_LENGTH: usize = 64 * 1024 * 1024;
+
+//a GltfLoader
+//tp GltfLoader
+/// A loader for .gltf and .glb files from the file system
+///
+/// The file is loaded and validated as a [Gltf], and the data for all of
+/// its buffers is loaded - from the binary chunk of a GLB file, from data:
+/// URIs, or from files whose (percent-encoded) URIs are relative to the
+/// directory containing the Gltf file. URIs that would escape that
+/// directory are rejected.
+///
+/// The buffers can be handed to [crate::ObjectData::gen_loaded_buffers].
+/// Images are not loaded, as they may not be required; [Self::resolver]
+/// provides a [ResourceResolver] to load them, for use with
+/// [crate::ObjectData::gen_images]
+#[derive(Debug, Clone)]
+pub struct GltfLoader {
+    /// The path to the .gltf or .glb file
+    path: PathBuf,
+    /// The directory that relative URIs are resolved against
+    base_dir: PathBuf,
+    /// The maximum length permitted for the Json chunk of a GLB file
+    max_json_length: usize,
+}
+
+//ip GltfLoader
+impl GltfLoader {
+    //cp new
+    /// Create a new [GltfLoader] for a .gltf or .glb file
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let base_dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        Self {
+            path,
+            base_dir,
+            max_json_length: DEFAULT_MAX_JSON_LENGTH,
+        }
+    }
+
+    //cp with_max_json_length
+    /// Set the maximum length permitted for the Json chunk of a GLB file
+    pub fn with_max_json_length(mut self, max_json_length: usize) -> Self {
+        self.max_json_length = max_json_length;
+        self
+    }
+
+    //ap path
+    /// Get the path of the Gltf file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    //ap base_dir
+    /// Get the directory that relative URIs are resolved against
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    //mp resolver
+    /// Get a [ResourceResolver] for the URIs of the Gltf file - data: URIs,
+    /// and paths relative to the directory of the Gltf file
+    ///
+    /// This may be used to resolve the image URIs with
+    /// [crate::ObjectData::gen_images]
+    pub fn resolver(&self) -> ResolverChain<'static> {
+        ResolverChain::new()
+            .with(DataUriResolver)
+            .with(DirResolver::new(self.base_dir.clone()))
+    }
+
+    //mp load_uri
+    /// Load the data for a URI using [Self::resolver], which must be at
+    /// least byte_length bytes
+    pub fn load_uri(&self, uri: &str, byte_length: usize) -> Result<Vec<u8>> {
+        let data = self.resolver().resolve_required(uri)?;
+        if data.len() < byte_length {
+            Err(Error::BufferTooShort)
+        } else {
+            Ok(data)
+        }
+    }
+
+    //mp load
+    /// Load the Gltf file and the data for all of its buffers, in Gltf
+    /// order
+    ///
+    /// The file is treated as a GLB file if it starts with the GLB magic
+    /// number, and as Gltf Json otherwise
+    ///
+    /// The min and max of any accessor that does not have them are
+    /// computed from the buffer data
+    pub fn load(&self) -> Result<(Gltf, Vec<Vec<u8>>)> {
+        let data = std::fs::read(&self.path)?;
+        let (mut gltf, mut opt_bin) = {
+            if data.starts_with(b"glTF") {
+                let (gltf, bin) = glb_load_slice(&data, self.max_json_length)?;
+                (gltf, bin.map(|b| b.to_vec()))
+            } else {
+                (Gltf::of_json_value(serde_json::from_slice(&data)?)?, None)
+            }
+        };
+        let resolver = self.resolver();
+        let mut buffers = vec![];
+        for (i, b) in gltf.buffers().iter().enumerate() {
+            let data = {
+                if i == 0 && b.uri().is_empty() {
+                    opt_bin.take().ok_or(Error::BufferRead)?
+                } else {
+                    resolver.resolve_required(b.uri())?
+                }
+            };
+            if data.len() < b.byte_length() {
+                return Err(Error::BufferTooShort);
+            }
+            buffers.push(data);
+        }
+        gltf.compute_accessor_bounds(&|b| buffers.get(usize::from(b)).map(|d| d.as_slice()))?;
+        Ok((gltf, buffers))
+    }
+}
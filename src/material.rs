@@ -8,6 +8,25 @@ use crate::deserialize;
 use crate::Named;
 use crate::{GltfTextureInfo, MaterialIndex};
 
+//a AlphaMode
+//tp AlphaMode
+/// The alpha mode of a Gltf material, which determines how the alpha value
+/// of its base color is interpreted
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum AlphaMode {
+    /// The alpha value is ignored, and the material is fully opaque
+    #[default]
+    Opaque,
+    /// The material is fully opaque or fully transparent, depending on
+    /// whether the alpha value is at least the alpha cutoff
+    Mask,
+    /// The alpha value is used to blend the material with the background
+    Blend,
+}
+
+//a GltfPbrMetallicRoughness
 //tp GltfPbrMetallicRoughness
 ///
 #[derive(Debug, Default)]
@@ -42,10 +61,42 @@ impl GltfPbrMetallicRoughness {
     }
 }
 
+//a GltfMaterialExtensions
+//tp GltfEmissiveStrength
+/// The KHR_materials_emissive_strength extension of a Gltf material, which
+/// scales its emissive factor to permit high dynamic range emission
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GltfEmissiveStrength {
+    /// The multiplier of the emissive factor; default is 1.0
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "emissiveStrength", default = "deserialize::f32_one")
+    )]
+    pub emissive_strength: f32,
+}
+
+//tp GltfMaterialExtensions
+/// The supported extensions of a Gltf material; others are ignored
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfMaterialExtensions {
+    /// KHR_materials_emissive_strength
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "KHR_materials_emissive_strength",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub emissive_strength: Option<GltfEmissiveStrength>,
+}
+
 //a GltfMaterial
 //tp GltfMaterial
 /// A type representing a Gltf Material -
-#[derive(Debug, Default)]
+#[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 pub struct GltfMaterial {
@@ -68,13 +119,40 @@ pub struct GltfMaterial {
     #[cfg_attr(feature = "serde", serde(rename = "emissiveFactor"))]
     pub emissive_factor: [f32; 3],
 
-    /// One of OPAQUE, MASK, BLEND
+    /// One of OPAQUE, MASK, BLEND; default is OPAQUE
     #[cfg_attr(feature = "serde", serde(rename = "alphaMode"))]
-    pub alpha_mode: Option<String>,
-    #[cfg_attr(feature = "serde", serde(rename = "alphaCutoff"))]
-    pub alpha_cutoff: f32,
+    pub alpha_mode: AlphaMode,
+    /// The alpha cutoff for MASK mode, if present; default is 0.5
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "alphaCutoff", skip_serializing_if = "Option::is_none")
+    )]
+    pub alpha_cutoff: Option<f32>,
+    /// If true then back faces should not be culled, and their normals
+    /// should be reversed when lit
     #[cfg_attr(feature = "serde", serde(rename = "doubleSided"))]
     pub double_sided: bool,
+    /// The extensions of the material that are supported
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub extensions: Option<GltfMaterialExtensions>,
+}
+
+//ip Default for GltfMaterial
+impl Default for GltfMaterial {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            normal_texture: None,
+            occlusion_texture: None,
+            emissive_texture: None,
+            pbr_metallic_roughness: None,
+            emissive_factor: [0.; 3],
+            alpha_mode: AlphaMode::Opaque,
+            alpha_cutoff: None,
+            double_sided: false,
+            extensions: None,
+        }
+    }
 }
 
 impl GltfMaterial {
@@ -90,6 +168,56 @@ impl GltfMaterial {
     pub fn emissive_texture(&self) -> &Option<GltfTextureInfo> {
         &self.emissive_texture
     }
+    //ap alpha_mode
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+    //ap alpha_cutoff
+    /// Get the alpha cutoff, which is only used in MASK mode; the default
+    /// is 0.5
+    pub fn alpha_cutoff(&self) -> f32 {
+        self.alpha_cutoff.unwrap_or(0.5)
+    }
+    //ap double_sided
+    pub fn double_sided(&self) -> bool {
+        self.double_sided
+    }
+    //ap base_color_factor
+    /// Get the linear base color factor (RGBA); the default is opaque white
+    ///
+    /// If the Gltf does not provide four values then the default is used
+    pub fn base_color_factor(&self) -> [f32; 4] {
+        self.pbr_metallic_roughness
+            .as_ref()
+            .and_then(|pbr| pbr.base_color_factor.as_ref())
+            .and_then(|c| c.as_slice().try_into().ok())
+            .unwrap_or([1.0; 4])
+    }
+    //ap metallic_roughness_factors
+    /// Get the metallic and roughness factors; the default for both is 1.0
+    pub fn metallic_roughness_factors(&self) -> (f32, f32) {
+        self.pbr_metallic_roughness
+            .as_ref()
+            .map_or((1.0, 1.0), |pbr| {
+                (pbr.metallic_factor, pbr.roughness_factor)
+            })
+    }
+    //ap emissive_strength
+    /// Get the emissive strength from the KHR_materials_emissive_strength
+    /// extension; the default is 1.0
+    pub fn emissive_strength(&self) -> f32 {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.emissive_strength.as_ref())
+            .map_or(1.0, |e| e.emissive_strength)
+    }
+    //ap emissive
+    /// Get the linear emissive color - the emissive factor scaled by the
+    /// emissive strength; this may exceed 1.0
+    pub fn emissive(&self) -> [f32; 3] {
+        let s = self.emissive_strength();
+        self.emissive_factor.map(|e| e * s)
+    }
 }
 
 //ip Named for GltfMaterial
@@ -13,15 +13,10 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[cfg(feature = "serde_json")]
-use serde_json::Value as JsonValue;
-#[cfg(not(feature = "serde_json"))]
-pub type JsonValue = ();
-
 use mod3d_base::Transformation;
 
+use crate::ValidationReport;
 use crate::{CameraIndex, MeshIndex, Named, NodeIndex, SkinIndex};
-use crate::{Error, Result};
 
 //a GltfNode
 //tp GltfNode
@@ -40,11 +35,25 @@ pub struct GltfNode {
     rotation: Option<[f32; 4]>,
     translation: Option<[f32; 3]>,
     scale: Option<[f32; 3]>,
-    weights: Option<JsonValue>,
+    /// Morph target weights for the mesh of the node, overriding those of
+    /// the mesh
+    weights: Option<Vec<f32>>,
     #[cfg_attr(feature = "serde", serde(skip))]
     local_transformation: Transformation,
+    /// A posed local transformation (e.g. from an animation) that overrides
+    /// the matrix or TRS of the node when the node is derived
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pose: Option<Transformation>,
+    /// Posed morph target weights (e.g. from an animation) that override
+    /// the weights of the node and its mesh
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pose_weights: Option<Vec<f32>>,
     #[cfg_attr(feature = "serde", serde(skip))]
     global_transformation: Transformation,
+    /// The parent of the node in the node hierarchy; this is derived
+    /// from the children of all the nodes
+    #[cfg_attr(feature = "serde", serde(skip))]
+    parent: Option<NodeIndex>,
     // optional: extensions, extras
 }
 
@@ -58,49 +67,94 @@ impl Named for GltfNode {
 
 //ip GltfNode
 impl GltfNode {
-    pub fn validate(&self, n: NodeIndex) -> Result<()> {
+    //mp validate
+    /// Validate the node, adding any issues to the report
+    pub fn validate(&self, n: NodeIndex, report: &mut ValidationReport) {
         if self.skin.is_some() && self.mesh.is_none() {
-            return Err(Error::BadJson(format!(
-                "Node {n} has a skin but no mesh which is illegal",
-            )));
+            report.error(
+                "NODE_SKIN_NO_MESH",
+                format!("/nodes/{n}/skin"),
+                "Node has a skin but no mesh which is illegal",
+            );
         }
         if self.matrix.is_some()
             && (self.rotation.is_some() || self.translation.is_some() || self.scale.is_some())
         {
-            return Err(Error::BadJson(format!(
-                "Node {n} has a matrix and some TRS",
-            )));
+            report.error(
+                "NODE_MATRIX_TRS",
+                format!("/nodes/{n}/matrix"),
+                "Node has a matrix and some TRS",
+            );
         }
 
-        if self.weights.is_some() {
-            return Err(Error::BadJson(format!(
-                "Node {n} has morpht target weights that are not supported",
-            )));
+        if self.weights.is_some() && self.mesh.is_none() {
+            report.error(
+                "NODE_WEIGHTS_NO_MESH",
+                format!("/nodes/{n}/weights"),
+                "Node has morph target weights but no mesh",
+            );
+        }
+        if self.skin.is_some()
+            && self.mesh.is_some()
+            && (self.matrix.is_some()
+                || self.rotation.is_some()
+                || self.translation.is_some()
+                || self.scale.is_some())
+        {
+            report.warning(
+                "NODE_SKINNED_MESH_LOCAL_TRANSFORMS",
+                format!("/nodes/{n}"),
+                "Node has a skinned mesh, which its local transform will not affect",
+            );
         }
-        Ok(())
     }
 
     pub fn derive(&mut self, parent_transformation: &Transformation) -> &Transformation {
-        self.local_transformation = Transformation::default();
-        if let Some(matrix) = self.matrix {
+        if let Some(pose) = self.pose {
+            self.local_transformation = pose;
+        } else if let Some(matrix) = self.matrix {
+            self.local_transformation = Transformation::default();
             self.local_transformation.from_mat4(matrix);
         } else {
-            if let Some(scale) = self.scale {
-                self.local_transformation.set_scale(scale);
-            }
-            if let Some(rotation) = self.rotation {
-                let rotation = (rotation[3], rotation[0], rotation[1], rotation[2]).into();
-                self.local_transformation.set_rotation(rotation);
-            }
-            if let Some(translation) = self.translation {
-                self.local_transformation.set_translation(translation);
-            }
+            let (translation, rotation, scale) = self.rest_trs();
+            self.local_transformation = transformation_of_trs(translation, rotation, scale);
         }
         self.global_transformation
             .combine(parent_transformation, &self.local_transformation);
         &self.global_transformation
     }
 
+    //ap rest_trs
+    /// Get the translation, rotation (as i, j, k, r) and scale of the node
+    /// as specified in the Gltf, using the defaults for those not specified
+    pub fn rest_trs(&self) -> ([f32; 3], [f32; 4], [f32; 3]) {
+        (
+            self.translation.unwrap_or([0., 0., 0.]),
+            self.rotation.unwrap_or([0., 0., 0., 1.]),
+            self.scale.unwrap_or([1., 1., 1.]),
+        )
+    }
+
+    //mp set_pose
+    /// Set (or clear) the posed local transformation of the node, used
+    /// instead of its matrix or TRS when the node is next derived
+    pub fn set_pose(&mut self, pose: Option<Transformation>) {
+        self.pose = pose;
+    }
+
+    //mp set_pose_weights
+    /// Set (or clear) the posed morph target weights of the node
+    pub fn set_pose_weights(&mut self, weights: Option<Vec<f32>>) {
+        self.pose_weights = weights;
+    }
+
+    //ap weights
+    /// Get the morph target weights of the node, if it overrides those of
+    /// its mesh - the posed weights if set, else those from the Gltf
+    pub fn weights(&self) -> Option<&[f32]> {
+        self.pose_weights.as_deref().or(self.weights.as_deref())
+    }
+
     pub fn is_root(&self) -> bool {
         self.children.is_empty()
     }
@@ -119,6 +173,15 @@ impl GltfNode {
     pub fn global_transformation(&self) -> &Transformation {
         &self.global_transformation
     }
+    pub fn local_transformation(&self) -> &Transformation {
+        &self.local_transformation
+    }
+    pub fn parent(&self) -> Option<NodeIndex> {
+        self.parent
+    }
+    pub fn set_parent(&mut self, parent: Option<NodeIndex>) {
+        self.parent = parent;
+    }
     pub fn set_mesh(&mut self, mesh: MeshIndex) {
         self.mesh = Some(mesh);
     }
@@ -147,3 +210,19 @@ impl GltfNode {
         }
     }
 }
+
+//fp transformation_of_trs
+/// Create a [Transformation] from a Gltf translation, rotation (as i, j, k,
+/// r) and scale
+pub fn transformation_of_trs(
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+) -> Transformation {
+    let mut t = Transformation::default();
+    t.set_scale(scale);
+    let rotation = (rotation[3], rotation[0], rotation[1], rotation[2]).into();
+    t.set_rotation(rotation);
+    t.set_translation(translation);
+    t
+}
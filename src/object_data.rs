@@ -2,18 +2,145 @@
 use mod3d_base::hierarchy::NodeEnumOp;
 use mod3d_base::{BufferAccessor, BufferData, ByteBuffer, Renderable};
 
-use crate::try_buf_parse_base64;
-use crate::Gltf;
+use crate::image_mime_type;
+use crate::resolver::ParseResolver;
 use crate::{
     AccessorIndex, BufferIndex, BufferUsage, ImageIndex, MaterialIndex, MeshIndex, NodeIndex,
-    PrimitiveIndex, SamplerIndex, TextureIndex,
+    PrimitiveIndex, SamplerIndex, SkinIndex, TextureIndex,
 };
-use crate::{Error, Result};
+use crate::{AlphaMode, Error, Result};
+use crate::{DataUriResolver, Gltf, GltfBuffer, GltfPrimitive, GltfSampler, ResourceResolver};
 use crate::{
-    Indexable, ODAccIndex, ODImagesIndex, ODMaterialsIndex, ODTexturesIndex, ODUses,
-    ODVerticesIndex,
+    Indexable, ODAccIndex, ODImagesIndex, ODMaterialsIndex, ODSkinsIndex, ODTexturesIndex, ODUse,
+    ODUses, ODVerticesIndex,
 };
 
+//a Synthetic
+//ti Synthetic
+/// A buffer that is generated by the [ObjectData] rather than provided by
+/// the Gltf
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Synthetic {
+    /// The materialised data of an accessor that has no buffer view, or
+    /// that is sparse
+    Accessor(AccessorIndex),
+    /// Sequential u32 indices 0..count for non-indexed primitives with
+    /// count vertices, and the index into the Vec<BufferAccessor> of the
+    /// accessor for them (once generated)
+    Indices(usize, Option<ODAccIndex>),
+}
+
+//fi index_count
+/// Get the number of indices of a primitive - the count of its indices
+/// accessor, or for a non-indexed primitive the count of its POSITION
+/// accessor
+fn index_count(gltf: &Gltf, primitive: &GltfPrimitive) -> Option<usize> {
+    if let Some(ia) = primitive.indices() {
+        Some(gltf[ia].count())
+    } else {
+        let pa = primitive.attribute(mod3d_base::VertexAttr::Position)?;
+        Some(gltf[pa].count())
+    }
+}
+
+//a ODMaterialTexture
+//tp ODMaterialTexture
+/// A texture of a material generated by [ObjectData::gen_od_materials], with
+/// the properties of the Gltf texture info that select how it is applied
+#[derive(Debug, Clone)]
+pub struct ODMaterialTexture {
+    /// The aspect of the material that the texture provides
+    pub aspect: mod3d_base::MaterialAspect,
+    /// The index of the texture in the Vec<> of textures generated by
+    /// [ObjectData::gen_textures]
+    pub texture: ODTexturesIndex,
+    /// The texture coordinate set (n in TEXCOORD_n) of the primitive
+    /// vertices to use for the texture
+    pub tex_coord: usize,
+    /// The scale of a normal texture, or the strength of an occlusion
+    /// texture; 1.0 for other aspects
+    pub scale: f32,
+}
+
+//fi unit_to_u8
+/// Quantise a value in the range 0.0 to 1.0 (clamping it if it is not) to
+/// 0 to 255
+fn unit_to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+//a ODMaterial
+//tp ODMaterial
+/// A material generated by [ObjectData::gen_od_materials], with its factors
+/// as linear floats and the properties that a renderer requires - such as
+/// to sort transparent geometry, or to disable back-face culling
+///
+/// This is the full description of the Gltf material; a
+/// mod3d_base::PbrMaterial can be created from it with
+/// [ODMaterial::pbr_material], but that loses information
+#[derive(Debug, Clone)]
+pub struct ODMaterial {
+    /// The textures used by the material
+    pub textures: Vec<ODMaterialTexture>,
+    /// How the alpha of the base color is to be used
+    pub alpha_mode: AlphaMode,
+    /// The alpha cutoff, if the alpha mode is [AlphaMode::Mask]
+    pub alpha_cutoff: f32,
+    /// True if back faces must not be culled
+    pub double_sided: bool,
+    /// The linear base color factor (RGBA)
+    pub base_color: [f32; 4],
+    /// The metallic factor
+    pub metallic: f32,
+    /// The roughness factor
+    pub roughness: f32,
+    /// The linear emissive color, including any emissive strength; this
+    /// may exceed 1.0
+    pub emissive: [f32; 3],
+}
+
+//ip Default for ODMaterial
+impl Default for ODMaterial {
+    /// The Gltf default material - opaque white, fully metallic and fully
+    /// rough, with no emission
+    fn default() -> Self {
+        Self {
+            textures: vec![],
+            alpha_mode: AlphaMode::Opaque,
+            alpha_cutoff: 0.5,
+            double_sided: false,
+            base_color: [1.0; 4],
+            metallic: 1.0,
+            roughness: 1.0,
+            emissive: [0.0; 3],
+        }
+    }
+}
+
+//ip ODMaterial
+impl ODMaterial {
+    //mp pbr_material
+    /// Create a mod3d_base::PbrMaterial for the material
+    ///
+    /// This is a lossy conversion: the base color and emissive color are
+    /// quantised to 8 bits, with an emissive color above 1.0 (from an
+    /// emissive strength) clamped; and the alpha mode, alpha cutoff,
+    /// double-sidedness, and texture coordinate sets and scales of the
+    /// textures are dropped
+    pub fn pbr_material(&self) -> mod3d_base::PbrMaterial {
+        let [r, g, b, a] = self.base_color.map(unit_to_u8);
+        let mut pbr_mat = mod3d_base::PbrMaterial::of_rgba(0xffffffff);
+        pbr_mat.set_rgba((r, g, b, a));
+        pbr_mat.set_mr(self.metallic, self.roughness);
+        let [r, g, b] = self.emissive.map(unit_to_u8);
+        pbr_mat.set_emissive_rgb((r, g, b));
+        for t in &self.textures {
+            pbr_mat.set_texture(t.aspect, t.texture.into());
+        }
+        pbr_mat
+    }
+}
+
 //a ObjectData
 //tp ObjectData
 /// The type that is used to construct mod3d_base from a Gltf
@@ -51,18 +178,24 @@ pub struct ObjectData {
     /// mesh node then *unlike* the GLTF specification (which says the joint
     /// pose should be used) the mesh is not posed, but placed at its static
     /// positioning given by the hierarchy of nodes from its root node
-    #[allow(dead_code)]
     joints_used: Vec<NodeIndex>,
     materials_used: ODUses<MaterialIndex, ODMaterialsIndex>,
 
+    /// Whether the default material is required (by a primitive that has no
+    /// material), and once generated its index into the Vec<> of materials
+    default_material: ODUse<ODMaterialsIndex>,
+
+    /// For each skin in the Gltf, the index into the Vec<> of skeletons (if
+    /// used by a skinned mesh node of the object)
+    skins_used: ODUses<SkinIndex, ODSkinsIndex>,
+
     /// For each image in the Gltf, the index into the Vec<> array (if used and it
     /// created the image without error)
     images_used: ODUses<ImageIndex, ODImagesIndex>,
 
-    /// For each sampler in the Gltf, the index into the Vec<> array (if used and it
-    /// created the image without error)
-    ///
-    /// Actually currently unused
+    /// For each sampler in the Gltf, whether it is used by a texture; the
+    /// sampler description is handed to the client when the texture is
+    /// generated, so there is no client-side data for it
     samplers_used: ODUses<SamplerIndex, ()>,
 
     /// For each sampler in the Gltf, the index into the Vec<> array (if used and it
@@ -71,8 +204,14 @@ pub struct ObjectData {
 
     /// A vec the same size as
     /// json_value. which maps a Json buffer to
-    /// the range of it that is used
+    /// the range of it that is used; this is followed by the usage of the
+    /// synthetic buffers
     buffer_usage: Vec<BufferUsage>,
+    /// The buffers that must be generated, for accessors that have no
+    /// buffer view (or are sparse) and for the indices of non-indexed
+    /// primitives; the usage of the buffer for the k'th is at
+    /// buffer_usage[N+k] where N is the number of buffers in the Gltf
+    synthetic_buffers: Vec<Synthetic>,
     /// For each accessor, the index into the Vec<BufferAccessor> (if used and it
     /// worked); same size as gltf.buffer_views
     accessors: Vec<Option<ODAccIndex>>,
@@ -142,18 +281,23 @@ impl ObjectData {
         let nodes_used = vec![];
         let joints_used = vec![];
         let materials_used = ODUses::new();
+        let skins_used = ODUses::new();
         let textures_used = ODUses::new();
         let images_used = ODUses::new();
         let samplers_used = ODUses::new();
         let buffer_usage = vec![Default::default(); num_buffers];
+        let synthetic_buffers = vec![];
         let meshes = vec![Default::default(); num_meshes];
         let accessors = vec![Default::default(); num_accessors];
         Self {
             nodes_used,
             joints_used,
             materials_used,
+            default_material: ODUse::Unknown,
+            skins_used,
             textures_used,
             buffer_usage,
+            synthetic_buffers,
             meshes,
             accessors,
             images_used,
@@ -168,7 +312,6 @@ impl ObjectData {
 
     //mi add_joint_node
     /// Add a joint node to the set of nodes used by this Object
-    #[allow(dead_code)]
     fn add_joint_node(&mut self, node: NodeIndex) {
         if !self.joints_used.contains(&node) {
             self.joints_used.push(node);
@@ -191,10 +334,21 @@ impl ObjectData {
         for eo in gltf.node_hierarchy().iter_from(nh_index.as_usize()) {
             if let NodeEnumOp::Push((_, n), _) = eo {
                 self.add_node(*n);
+                if let Some(s) = gltf[*n].skin() {
+                    for j in gltf[s].joints() {
+                        self.add_joint_node(*j);
+                    }
+                }
             }
         }
     }
 
+    //ap joints_used
+    /// Get the joint nodes used by the skinned meshes of the objects
+    pub fn joints_used(&self) -> &[NodeIndex] {
+        &self.joints_used
+    }
+
     //mi use_buffer
     /// Record the use of a portion of a buffer in its Usage
     ///
@@ -211,11 +365,78 @@ impl ObjectData {
         self[buffer].use_buffer(as_index, byte_start, byte_length);
     }
 
+    //mi synthetic_index
+    /// Get the index into the synthetic buffers of the first that matches
+    fn synthetic_index<P: Fn(&Synthetic) -> bool>(&self, p: P) -> Option<usize> {
+        self.synthetic_buffers.iter().position(p)
+    }
+
+    //mi synthetic_buffer_index
+    /// Get the index of a synthetic buffer in the buffer usage
+    fn synthetic_buffer_index(&self, k: usize) -> BufferIndex {
+        let num_buffers = self.buffer_usage.len() - self.synthetic_buffers.len();
+        (num_buffers + k).into()
+    }
+
+    //mi synthetic_buffer
+    /// Get the index of the synthetic buffer (in the buffer usage) for an
+    /// accessor, if it has one
+    fn synthetic_buffer(&self, accessor: AccessorIndex) -> Option<BufferIndex> {
+        self.synthetic_index(|s| *s == Synthetic::Accessor(accessor))
+            .map(|k| self.synthetic_buffer_index(k))
+    }
+
+    //mi synthetic_indices
+    /// Get the index into the synthetic buffers of the indices for a
+    /// non-indexed primitive with a number of vertices, if it has one
+    fn synthetic_indices(&self, count: usize) -> Option<usize> {
+        self.synthetic_index(|s| matches!(s, Synthetic::Indices(c, _) if *c == count))
+    }
+
+    //mi add_synthetic_indices
+    /// Add a synthetic buffer of indices for a non-indexed primitive with a
+    /// number of vertices (if there is not already one), and use all of it
+    /// as index data
+    fn add_synthetic_indices(&mut self, count: usize) {
+        if self.synthetic_indices(count).is_some() {
+            return;
+        }
+        let bi: BufferIndex = self.buffer_usage.len().into();
+        self.buffer_usage.push(Default::default());
+        self.synthetic_buffers.push(Synthetic::Indices(count, None));
+        self.use_buffer(true, bi, 0, count * 4);
+    }
+
+    //mi add_synthetic_buffer
+    /// Add a synthetic buffer for an accessor (if it does not already have
+    /// one), and mark the buffers its data comes from as required
+    fn add_synthetic_buffer(&mut self, gltf: &Gltf, accessor: AccessorIndex) -> BufferIndex {
+        if let Some(bi) = self.synthetic_buffer(accessor) {
+            return bi;
+        }
+        let acc = &gltf[accessor];
+        let mut views = vec![];
+        if let Some(bv) = acc.buffer_view() {
+            views.push(bv);
+        }
+        if let Some(sparse) = acc.sparse() {
+            views.push(sparse.indices.buffer_view);
+            views.push(sparse.values.buffer_view);
+        }
+        for bv in views {
+            self[gltf[bv].buffer()].set_required();
+        }
+        let bi = self.buffer_usage.len().into();
+        self.buffer_usage.push(Default::default());
+        self.synthetic_buffers.push(Synthetic::Accessor(accessor));
+        bi
+    }
+
     //mi derive_uses_of_meshes
     /// Fill out the meshes and buffer regions that are used
     fn derive_uses_of_meshes(&mut self, gltf: &Gltf) {
         for n in 0..self.nodes_used.len() {
-            let ni: NodeIndex = n.into();
+            let ni = self.nodes_used[n];
             let node = &gltf[ni];
             if let Some(node_mesh) = node.mesh() {
                 let mesh = &mut self[node_mesh];
@@ -238,12 +459,21 @@ impl ObjectData {
                 for p in mesh.primitives() {
                     if let Some(a) = p.indices() {
                         accessors.push((true, a));
+                    } else if let Some(count) = index_count(gltf, p) {
+                        self.add_synthetic_indices(count);
                     }
                     for (_, a) in p.attributes() {
                         accessors.push((false, *a));
                     }
+                    for t in p.targets() {
+                        for (_, a) in t {
+                            accessors.push((false, *a));
+                        }
+                    }
                     if let Some(m) = p.material() {
                         self.materials_used.set_required(m);
+                    } else {
+                        self.default_material = ODUse::Required;
                     }
                 }
             }
@@ -251,11 +481,35 @@ impl ObjectData {
         accessors
     }
 
+    //mi derive_uses_of_skins
+    /// Fill out the skins used by skinned mesh nodes, and generate the list
+    /// of inverse bind matrix accessors that they use
+    fn derive_uses_of_skins(&mut self, gltf: &Gltf) -> Vec<(bool, AccessorIndex)> {
+        let mut accessors = vec![];
+        for n in 0..self.nodes_used.len() {
+            let ni = self.nodes_used[n];
+            if let Some(s) = gltf[ni].skin() {
+                if self.skins_used.is_required(s) {
+                    continue;
+                }
+                self.skins_used.set_required(s);
+                if let Some(a) = gltf[s].inverse_bind_matrices() {
+                    accessors.push((false, a));
+                }
+            }
+        }
+        accessors
+    }
+
     //mi derive_uses_of_accessors
     /// Fill out the meshes and buffer regions that are used
     fn derive_uses_of_accessors(&mut self, gltf: &Gltf, accessors: Vec<(bool, AccessorIndex)>) {
         for (as_index, a) in accessors {
-            if let Some(bv) = gltf[a].buffer_view() {
+            if gltf[a].is_synthetic() {
+                let buffer = self.add_synthetic_buffer(gltf, a);
+                let byte_length = gltf[a].count() * gltf[a].ele_byte_size();
+                self.use_buffer(as_index, buffer, 0, byte_length);
+            } else if let Some(bv) = gltf[a].buffer_view() {
                 let buffer = gltf[bv].buffer();
                 let byte_start = gltf[bv].byte_offset();
                 let byte_length = gltf[bv].byte_length();
@@ -295,7 +549,19 @@ impl ObjectData {
         for (ti, _use) in self.textures_used.iter_required() {
             let texture = &gltf[ti];
             self.images_used.set_required(texture.image());
-            self.samplers_used.set_required(texture.sampler());
+            if let Some(s) = texture.sampler() {
+                self.samplers_used.set_required(s);
+            }
+        }
+        for i in 0..gltf.images().len() {
+            let ii: ImageIndex = i.into();
+            if !self.images_used.is_required(ii) || gltf[ii].uri().is_some() {
+                continue;
+            }
+            if let Some(bv) = gltf[ii].buffer_view() {
+                let bv = &gltf[bv];
+                self[bv.buffer()].use_image_data(bv.byte_offset(), bv.byte_length());
+            }
         }
     }
 
@@ -304,12 +570,11 @@ impl ObjectData {
     /// buffers) are required for the selected objects
     pub fn derive_uses(&mut self, gltf: &Gltf) {
         self.derive_uses_of_meshes(gltf);
-        let accessors = self.derive_uses_of_materials(gltf);
+        let mut accessors = self.derive_uses_of_materials(gltf);
+        accessors.extend(self.derive_uses_of_skins(gltf));
         self.derive_uses_of_accessors(gltf, accessors);
         self.derive_uses_of_textures(gltf);
         self.derive_uses_of_images_and_samplers(gltf);
-        eprintln!("gltf : object_data : does not yet derive buffer uses of images - it won't gen_buffers for them");
-        // derive buffer uses of images
         self.images_used.complete_uses();
     }
 
@@ -325,25 +590,27 @@ impl ObjectData {
         }
     }
 
-    //mp gen_buffers
-    /// Generate a Vec of all the buffers required for the objects used in Gltf
+    //mi gen_buffers_by
+    /// Generate a Vec of all the buffers required for the objects used in
+    /// Gltf, with the data for each Gltf buffer that is used provided by a
+    /// function (if it does not come from opt_buffer_0)
     ///
-    /// Drop the buffer descriptors in the GltfJsonValue as we go
-    ///
-    /// The first comes from opt_buffer_0 if Some; this may come from
-    /// a Glb file binary chunk, for example.
+    /// The min and max of the accessors that do not have them are then
+    /// computed from the buffers that are used
     ///
-    /// The rest are created by invoking buf_parse on the Uri and
-    /// byte_length specified in the [GltfJsonValue]
-    pub fn gen_buffers<B, BP>(
+    /// Finally a buffer is created for each accessor that has no buffer view
+    /// or is sparse, with its data materialised from the other buffers
+    fn gen_buffers_by<B, BF>(
         &mut self,
         gltf: &mut Gltf,
-        buf_parse: &BP,
+        buffer_of: &mut BF,
         opt_buffer_0: Option<B>,
     ) -> Result<Vec<B>>
     where
-        BP: Fn(&str, usize) -> Result<B>,
+        B: AsRef<[u8]> + From<Vec<u8>>,
+        BF: FnMut(BufferIndex, GltfBuffer) -> Result<B>,
     {
+        let num_buffers = gltf.buffers().len();
         let mut result = vec![];
         let mut used_opt_0 = false;
         if let Some(b) = self.first_buffer() {
@@ -353,7 +620,7 @@ impl ObjectData {
                 b.set_buffer_index(0.into());
             }
         }
-        for i in 0..self.buffer_usage.len() {
+        for i in 0..num_buffers {
             let bi: BufferIndex = i.into();
             let buffer = gltf.take_buffer_data(bi);
             if !self[bi].is_used() {
@@ -361,19 +628,98 @@ impl ObjectData {
             }
             if i > 0 || !used_opt_0 {
                 self[bi].set_buffer_index(result.len().into());
-                result.push(buf_parse(buffer.uri(), buffer.byte_length())?);
+                result.push(buffer_of(bi, buffer)?);
             }
         }
+        let buffer = |b: BufferIndex| {
+            let bu = &self[b];
+            bu.is_used()
+                .then(|| result[bu.buffer_index().as_usize()].as_ref())
+        };
+        gltf.compute_accessor_bounds(&buffer)?;
+        for k in 0..self.synthetic_buffers.len() {
+            let data = match self.synthetic_buffers[k] {
+                Synthetic::Accessor(a) => {
+                    let buffer = |b: BufferIndex| {
+                        let bu = &self[b];
+                        bu.is_used()
+                            .then(|| result[bu.buffer_index().as_usize()].as_ref())
+                    };
+                    gltf.accessor_bytes(a, &buffer)?
+                }
+                Synthetic::Indices(count, _) => {
+                    (0..count as u32).flat_map(|i| i.to_le_bytes()).collect()
+                }
+            };
+            self.buffer_usage[num_buffers + k].set_buffer_index(result.len().into());
+            result.push(data.into());
+        }
         Ok(result)
     }
 
+    //mp gen_buffers
+    /// Generate a Vec of all the buffers required for the objects used in Gltf
+    ///
+    /// Drop the buffer descriptors in the GltfJsonValue as we go
+    ///
+    /// The first comes from opt_buffer_0 if Some; this may come from
+    /// a Glb file binary chunk, for example.
+    ///
+    /// The rest are created by resolving the URI specified in the
+    /// [GltfJsonValue] with the resolver; the data must be at least the
+    /// byte_length of the buffer
+    ///
+    /// Finally a buffer is created for each accessor that has no buffer view
+    /// or is sparse, with its data materialised from the other buffers
+    pub fn gen_buffers<B, RR>(
+        &mut self,
+        gltf: &mut Gltf,
+        resolver: &RR,
+        opt_buffer_0: Option<B>,
+    ) -> Result<Vec<B>>
+    where
+        B: AsRef<[u8]> + From<Vec<u8>>,
+        RR: ResourceResolver + ?Sized,
+    {
+        let mut buffer_of = |_: BufferIndex, buffer: GltfBuffer| {
+            let data = resolver.resolve_required(buffer.uri())?;
+            if data.len() < buffer.byte_length() {
+                Err(Error::BufferTooShort)
+            } else {
+                Ok(data.into())
+            }
+        };
+        self.gen_buffers_by(gltf, &mut buffer_of, opt_buffer_0)
+    }
+
+    //mp gen_loaded_buffers
+    /// Generate a Vec of all the buffers required for the objects used in
+    /// Gltf, given the data for every buffer of the Gltf (in Gltf order) -
+    /// such as those returned by a [crate::GltfLoader]
+    ///
+    /// The buffers that are not used by the objects are dropped
+    pub fn gen_loaded_buffers<B>(&mut self, gltf: &mut Gltf, buffers: Vec<B>) -> Result<Vec<B>>
+    where
+        B: AsRef<[u8]> + From<Vec<u8>>,
+    {
+        let mut buffers: Vec<Option<B>> = buffers.into_iter().map(Some).collect();
+        let mut buffer_of = |bi: BufferIndex, _: GltfBuffer| {
+            buffers
+                .get_mut(bi.as_usize())
+                .and_then(|b| b.take())
+                .ok_or(Error::BufferRead)
+        };
+        self.gen_buffers_by(gltf, &mut buffer_of, None)
+    }
+
     //mp gen_byte_buffers
     /// Generate a Vec of all the Vec<u8> buffers required for the objects used
     /// in the Gltf
     ///
-    /// This is the same as [gen_buffers] except that it requires the buffer
-    /// type by Vec<u8>, and it also implicitly supports base64 decode of data:
-    /// URIs
+    /// This is the same as [Self::gen_buffers] except that it requires the
+    /// buffer type be Vec<u8>, and the URIs are resolved by base64 decode of
+    /// data: URIs or by invoking buf_parse with the URI and the byte length
+    /// of the buffer
     pub fn gen_byte_buffers<BP>(
         &mut self,
         gltf: &mut Gltf,
@@ -383,14 +729,18 @@ impl ObjectData {
     where
         BP: Fn(&str, usize) -> Result<Vec<u8>>,
     {
-        let bp = |uri: &str, byte_length: usize| {
-            if let Some(buf) = try_buf_parse_base64(uri, byte_length)? {
-                Ok(buf)
+        let mut buffer_of = |_: BufferIndex, buffer: GltfBuffer| {
+            let data = match DataUriResolver.resolve(buffer.uri())? {
+                Some(data) => data,
+                None => buf_parse(buffer.uri(), buffer.byte_length())?,
+            };
+            if data.len() < buffer.byte_length() {
+                Err(Error::BufferTooShort)
             } else {
-                buf_parse(uri, byte_length)
+                Ok(data)
             }
         };
-        self.gen_buffers(gltf, &bp, opt_buffer_0)
+        self.gen_buffers_by(gltf, &mut buffer_of, opt_buffer_0)
     }
 
     //mp gen_buffer_data
@@ -431,32 +781,67 @@ impl ObjectData {
     }
 
     //mp gen_images
-    /// Generate a Vec of all the images
-    pub fn gen_images<Image, F>(&mut self, gltf: &Gltf, get_image: &F) -> Result<Vec<Image>>
+    /// Generate a Vec of all the images, with the client creating each
+    /// image from its encoded data (such as the contents of a PNG file) and
+    /// mime type
+    ///
+    /// The data of an image is that of its URI, as resolved by the
+    /// resolver, or the bytes of its buffer view (taken from the buffers
+    /// generated by gen_buffers)
+    ///
+    /// If the Gltf does not specify the mime type of an image then it is
+    /// determined from the image data itself
+    ///
+    /// An error returned by the client for an image is returned as
+    /// [Error::ImageLoad], with the client's error as its source
+    pub fn gen_images<'buffers, Image, B, BF, RR, F>(
+        &mut self,
+        gltf: &Gltf,
+        buffer: &BF,
+        resolver: &RR,
+        get_image: &F,
+    ) -> Result<Vec<Image>>
     where
-        F: Fn((usize, usize, usize), &str) -> std::result::Result<Image, String>,
+        B: AsRef<[u8]> + ?Sized + 'buffers,
+        BF: Fn(usize) -> &'buffers B,
+        RR: ResourceResolver + ?Sized,
+        F: Fn(&[u8], &str) -> std::result::Result<Image, Box<dyn std::error::Error + Send + Sync>>,
     {
         let mut result = vec![];
         for (ii, image_use) in self.images_used.iter_mut_required() {
             let image = &gltf[ii];
-            let od_image = {
+            let data = {
                 if let Some(uri) = image.uri() {
-                    get_image((0, 0, 0), uri)
-                } else {
-                    let bv = &gltf[image.buffer_view()];
+                    std::borrow::Cow::Owned(resolver.resolve_required(uri)?)
+                } else if let Some(bv) = image.buffer_view() {
+                    let bv = &gltf[bv];
                     // Note the use of self.buffer_usage rather than self[bv.buffer()] which would be safer
                     //
                     // This is because self is partially borrowed mutably
-                    let buffer = self.buffer_usage[bv.buffer().as_usize()].buffer_index();
-                    let byte_offset = bv.byte_offset();
-                    let byte_length = bv.byte_length();
-                    get_image(
-                        (buffer.as_usize(), byte_offset, byte_length),
-                        image.mime_type(),
-                    )
+                    let buffer = buffer(
+                        self.buffer_usage[bv.buffer().as_usize()]
+                            .buffer_index()
+                            .as_usize(),
+                    );
+                    let Some(data) = buffer.as_ref().get(bv.byte_offset()..bv.byte_end()) else {
+                        return Err(Error::BufferTooShort);
+                    };
+                    std::borrow::Cow::Borrowed(data)
+                } else {
+                    return Err(Error::BadJson(format!(
+                        "Image {ii} has neither a URI nor a buffer view"
+                    )));
                 }
-            }
-            .map_err(|e| Error::ImageLoad { reason: e })?;
+            };
+            let mime_type = {
+                if !image.mime_type().is_empty() {
+                    image.mime_type()
+                } else {
+                    image_mime_type(&data).unwrap_or_default()
+                }
+            };
+            let od_image = get_image(&data, mime_type)
+                .map_err(|source| Error::ImageLoad { image: ii, source })?;
             let n = result.len();
             result.push(od_image);
             image_use.set_use(n.into());
@@ -464,22 +849,67 @@ impl ObjectData {
         Ok(result)
     }
 
+    //mp gen_byte_images
+    /// Generate a Vec of all the images
+    ///
+    /// This is the same as [Self::gen_images] except that the URIs are
+    /// resolved by base64 decode of data: URIs, or by invoking image_parse
+    /// with the URI and a byte length of 0
+    ///
+    /// Images in files relative to a directory are more simply loaded by
+    /// [Self::gen_images] with a [crate::DirResolver]
+    pub fn gen_byte_images<'buffers, Image, B, BF, IP, F>(
+        &mut self,
+        gltf: &Gltf,
+        buffer: &BF,
+        image_parse: &IP,
+        get_image: &F,
+    ) -> Result<Vec<Image>>
+    where
+        B: AsRef<[u8]> + ?Sized + 'buffers,
+        BF: Fn(usize) -> &'buffers B,
+        IP: Fn(&str, usize) -> Result<Vec<u8>>,
+        F: Fn(&[u8], &str) -> std::result::Result<Image, Box<dyn std::error::Error + Send + Sync>>,
+    {
+        self.gen_images(gltf, buffer, &ParseResolver(image_parse), get_image)
+    }
+
     //mi make_accessor
+    /// Make a [BufferAccessor] for an accessor
+    ///
+    /// A [BufferAccessor] has no notion of matrix column padding, so a
+    /// vertex attribute that is a matrix with padded columns is rejected
     fn make_accessor<'buffers, F, R>(
         &self,
         gltf: &Gltf,
         buffer_data: &F,
         is_index: bool,
         acc: AccessorIndex,
-    ) -> BufferAccessor<'buffers, R>
+    ) -> Result<BufferAccessor<'buffers, R>>
     where
         F: Fn(usize) -> &'buffers BufferData<'buffers, R>,
         R: Renderable + ?Sized,
     {
         let ba = &gltf[acc];
-        let bv = ba.buffer_view().unwrap();
-        let bv = &gltf[bv];
-        let buffer = &self[bv.buffer()];
+        let component_byte_length = ba.component_type().byte_length();
+        if !is_index && ba.accessor_type().is_padded(component_byte_length) {
+            return Err(Error::BadJson(format!(
+                "Accessor {acc} has padded matrix columns, which are not supported for vertex attributes"
+            )));
+        }
+        let (bi, byte_start, byte_stride) = {
+            if let Some(bi) = self.synthetic_buffer(acc) {
+                (bi, 0, ba.ele_byte_size())
+            } else {
+                let bv = &gltf[ba.buffer_view().unwrap()];
+                (
+                    bv.buffer(),
+                    bv.byte_offset() + ba.byte_offset(),
+                    bv.byte_stride(ba.ele_byte_size()),
+                )
+            }
+        };
+        let buffer = &self[bi];
         let data = {
             if is_index {
                 buffer.index_bd()
@@ -488,8 +918,7 @@ impl ObjectData {
             }
         };
         let data = buffer_data(data.as_usize());
-        let byte_offset = ba.byte_offset() + bv.byte_offset() - (data.byte_offset as usize);
-        let byte_stride = bv.byte_stride(ba.ele_byte_size());
+        let byte_offset = byte_start - (data.byte_offset as usize);
         let count = {
             if is_index {
                 ba.count()
@@ -497,14 +926,15 @@ impl ObjectData {
                 ba.elements_per_data()
             }
         };
-        eprintln!("make_accessor ba:? {data:?}, {count}, {byte_offset}, {byte_stride}");
-        BufferAccessor::new(
+        let mut buffer_accessor = BufferAccessor::new(
             data,
             count as u32,
             ba.component_type(),
             byte_offset as u32,
             byte_stride as u32,
-        )
+        );
+        buffer_accessor.set_normalized(ba.normalized());
+        Ok(buffer_accessor)
     }
 
     //mp gen_accessors
@@ -513,11 +943,14 @@ impl ObjectData {
     ///
     /// Should be invoked after gen_buffer_data has returned a Vec<> of the
     /// BufferData
+    ///
+    /// Fails if a vertex attribute is a matrix whose columns are padded
+    /// (such as a MAT3 of 8-bit components)
     pub fn gen_accessors<'buffers, F, R>(
         &mut self,
         gltf: &Gltf,
         buffer_data: &F,
-    ) -> Vec<BufferAccessor<'buffers, R>>
+    ) -> Result<Vec<BufferAccessor<'buffers, R>>>
     where
         F: Fn(usize) -> &'buffers BufferData<'buffers, R>,
         R: Renderable + ?Sized,
@@ -534,21 +967,105 @@ impl ObjectData {
                     if self[ia].is_some() {
                         continue;
                     }
-                    let b = self.make_accessor(gltf, buffer_data, true, ia);
+                    let b = self.make_accessor(gltf, buffer_data, true, ia)?;
                     self[ia] = Some(buffer_accessors.len().into());
                     buffer_accessors.push(b);
+                } else if let Some(k) = index_count(gltf, p).and_then(|c| self.synthetic_indices(c))
+                {
+                    if let Synthetic::Indices(count, None) = self.synthetic_buffers[k] {
+                        let bi = self.synthetic_buffer_index(k);
+                        let data = buffer_data(self[bi].index_bd().as_usize());
+                        let b = BufferAccessor::new(
+                            data,
+                            count as u32,
+                            mod3d_base::BufferElementType::UInt32,
+                            0,
+                            4,
+                        );
+                        self.synthetic_buffers[k] =
+                            Synthetic::Indices(count, Some(buffer_accessors.len().into()));
+                        buffer_accessors.push(b);
+                    }
                 }
                 for (_, va) in p.attributes() {
                     if self[*va].is_some() {
                         continue;
                     }
-                    let b = self.make_accessor(gltf, buffer_data, false, *va);
+                    let b = self.make_accessor(gltf, buffer_data, false, *va)?;
                     self[*va] = Some(buffer_accessors.len().into());
                     buffer_accessors.push(b);
                 }
+                for (_, ta) in p.targets().iter().flatten() {
+                    if self[*ta].is_some() {
+                        continue;
+                    }
+                    let b = self.make_accessor(gltf, buffer_data, false, *ta)?;
+                    self[*ta] = Some(buffer_accessors.len().into());
+                    buffer_accessors.push(b);
+                }
+            }
+        }
+        Ok(buffer_accessors)
+    }
+
+    //mp morph_targets
+    /// Get the morph targets of a primitive of a mesh, as the indices into
+    /// the Vec<BufferAccessor> generated by gen_accessors of the
+    /// displacements for each attribute of each target
+    ///
+    /// Should be invoked after gen_accessors; attributes whose accessors
+    /// were not generated are omitted
+    pub fn morph_targets(
+        &self,
+        gltf: &Gltf,
+        mesh: MeshIndex,
+        primitive: PrimitiveIndex,
+    ) -> Vec<Vec<(mod3d_base::VertexAttr, ODAccIndex)>> {
+        gltf[mesh][primitive]
+            .targets()
+            .iter()
+            .map(|t| {
+                t.iter()
+                    .filter_map(|(va, ta)| self[*ta].map(|a| (*va, a)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    //ap primitive_material
+    /// Get the index (in the Vec<> of materials generated by
+    /// [Self::gen_materials]) of the material used by a primitive - that of
+    /// its material, or the default material if it does not specify one
+    ///
+    /// This is the material index given to the primitive by
+    /// [Self::gen_object]
+    pub fn primitive_material(
+        &self,
+        gltf: &Gltf,
+        mesh: MeshIndex,
+        primitive: PrimitiveIndex,
+    ) -> Option<ODMaterialsIndex> {
+        if let Some(m) = gltf[mesh][primitive].material() {
+            self.materials_used[m].data().copied()
+        } else {
+            self.default_material()
+        }
+    }
+
+    //mi primitive_indices
+    /// Get the index into the Vec<BufferAccessor> of the indices of a
+    /// primitive - generated for its indices accessor, or for the synthetic
+    /// indices of a non-indexed primitive
+    fn primitive_indices(&self, gltf: &Gltf, primitive: &GltfPrimitive) -> Option<ODAccIndex> {
+        if let Some(ia) = primitive.indices() {
+            self[ia]
+        } else {
+            let k = self.synthetic_indices(index_count(gltf, primitive)?)?;
+            match self.synthetic_buffers[k] {
+                Synthetic::Indices(_, od_acc) => od_acc,
+                _ => None,
             }
         }
-        buffer_accessors
     }
 
     //mp gen_vertices
@@ -571,7 +1088,7 @@ impl ObjectData {
             }
             let mesh = &gltf[mi];
             for (pi, p) in mesh.primitives().iter().enumerate() {
-                let Some(ia) = p.indices() else {
+                let Some(ia) = self.primitive_indices(gltf, p) else {
                     continue;
                 };
                 let mut pa = None;
@@ -584,9 +1101,6 @@ impl ObjectData {
                 let Some(pa) = pa else {
                     continue;
                 };
-                let Some(ia) = self[ia] else {
-                    continue;
-                };
                 let Some(pa) = self[*pa] else {
                     continue;
                 };
@@ -613,92 +1127,225 @@ impl ObjectData {
     //mp gen_textures
     /// Generate textures from the objects in the Gltf, given images
     /// that have been generated already
+    ///
+    /// The texture_of_image function is invoked with each image and the
+    /// sampler (wrapping and filtering) that the texture uses; if the
+    /// texture does not specify a sampler then the default
+    /// [GltfSampler] is provided
+    ///
+    /// If a texture specifies a sampler that is not in the Gltf then an
+    /// error is returned
     pub fn gen_textures<'textures, F, I, R, T>(
         &mut self,
         gltf: &Gltf,
         image: &F,
         texture_of_image: &T,
-    ) -> Vec<mod3d_base::Texture<'textures, R>>
+    ) -> Result<Vec<mod3d_base::Texture<'textures, R>>>
     where
         F: Fn(usize) -> &'textures I,
         I: 'textures,
-        T: Fn(&'textures I) -> mod3d_base::Texture<'textures, R>,
+        T: Fn(&'textures I, &GltfSampler) -> mod3d_base::Texture<'textures, R>,
         R: Renderable + ?Sized,
     {
+        let default_sampler = GltfSampler::default();
         let mut textures = vec![];
         for (ti, texture_use) in self.textures_used.iter_mut_required() {
             let texture = &gltf[ti];
             let image = image(self.images_used[texture.image].data().unwrap().as_usize());
-            let model_texture = texture_of_image(image);
+            let sampler = {
+                if let Some(s) = texture.sampler() {
+                    gltf.samplers().get(s.as_usize()).ok_or_else(|| {
+                        Error::BadJson(format!("Texture {ti} has sampler {s} out of range"))
+                    })?
+                } else {
+                    &default_sampler
+                }
+            };
+            let model_texture = texture_of_image(image, sampler);
             let n = textures.len();
             textures.push(model_texture);
             texture_use.set_use(n.into());
         }
-        textures
+        Ok(textures)
     }
 
     //mp gen_materials
-    // Create materials
+    /// Generate the mod3d_base::PbrMaterial materials used by the objects,
+    /// with the textures (as generated by [Self::gen_textures]) that they
+    /// use
+    ///
+    /// The default material is appended if any primitive used does not
+    /// specify a material
+    ///
+    /// A mod3d_base::PbrMaterial has no texture coordinate set or scale for
+    /// its textures, so these (and the other properties dropped by
+    /// [ODMaterial::pbr_material]) are lost; use [Self::gen_od_materials]
+    /// instead of this method to retain them
     pub fn gen_materials(&mut self, gltf: &Gltf) -> Vec<mod3d_base::PbrMaterial> {
+        self.gen_od_materials(gltf)
+            .iter()
+            .map(ODMaterial::pbr_material)
+            .collect()
+    }
+
+    //mp gen_od_materials
+    /// Generate the materials used by the objects as [ODMaterial], with the
+    /// textures (as generated by [Self::gen_textures]) that they use and
+    /// the texture coordinate set and scale for each
+    ///
+    /// The materials are in the same order as for [Self::gen_materials];
+    /// only one of the two should be invoked
+    pub fn gen_od_materials(&mut self, gltf: &Gltf) -> Vec<ODMaterial> {
         let mut materials = vec![];
 
         for (mi, material_use) in self.materials_used.iter_mut_required() {
             let material = &gltf[mi];
-            let mut pbr_mat = mod3d_base::PbrMaterial::of_rgba(0xff112233);
+            let mut texture_infos = vec![];
+            use mod3d_base::MaterialAspect::*;
             if let Some(ti) = material.normal_texture() {
-                if let Some(ti) = self.textures_used[ti.index()].data() {
-                    pbr_mat.set_texture(mod3d_base::MaterialAspect::Normal, ti.into());
-                }
+                texture_infos.push((Normal, ti, ti.scale()));
             }
             if let Some(ti) = material.occlusion_texture() {
-                if let Some(ti) = self.textures_used[ti.index()].data() {
-                    pbr_mat.set_texture(mod3d_base::MaterialAspect::Occlusion, ti.into());
-                }
+                texture_infos.push((Occlusion, ti, ti.strength()));
             }
             if let Some(ti) = material.emissive_texture() {
-                if let Some(ti) = self.textures_used[ti.index()].data() {
-                    pbr_mat.set_texture(mod3d_base::MaterialAspect::Emission, ti.into());
-                }
+                texture_infos.push((Emission, ti, 1.0));
             }
-            pbr_mat.set_rgba((255, 255, 255, 255));
             if let Some(pbr) = material.pbr_metallic_roughness() {
                 if let Some(ti) = pbr.base_color_texture() {
-                    if let Some(ti) = self.textures_used[ti.index()].data() {
-                        pbr_mat.set_texture(mod3d_base::MaterialAspect::Color, ti.into());
-                    }
+                    texture_infos.push((Color, ti, 1.0));
                 }
                 if let Some(ti) = pbr.metallic_roughness_texture() {
-                    if let Some(ti) = self.textures_used[ti.index()].data() {
-                        pbr_mat
-                            .set_texture(mod3d_base::MaterialAspect::MetallicRoughness, ti.into());
-                    }
-                }
-                if let Some(color) = pbr.base_color_factor.as_ref() {
-                    // by spec there must be 4 entries of 0.0 to 1.0 inclusive
-                    if color.len() == 4 {
-                        let r = (color[0] * 255.0) as u8;
-                        let g = (color[1] * 255.0) as u8;
-                        let b = (color[2] * 255.0) as u8;
-                        let a = (color[3] * 255.0) as u8;
-                        pbr_mat.set_rgba((r, g, b, a));
-                    }
+                    texture_infos.push((MetallicRoughness, ti, 1.0));
                 }
-                pbr_mat.set_mr(pbr.metallic_factor, pbr.roughness_factor);
             }
-            {
-                let r = (material.emissive_factor[0] * 255.0) as u8;
-                let g = (material.emissive_factor[1] * 255.0) as u8;
-                let b = (material.emissive_factor[2] * 255.0) as u8;
-                pbr_mat.set_emissive_rgb((r, g, b));
+            let mut textures = vec![];
+            for (aspect, ti, scale) in texture_infos {
+                if let Some(texture) = self.textures_used[ti.index()].data() {
+                    textures.push(ODMaterialTexture {
+                        aspect,
+                        texture: *texture,
+                        tex_coord: ti.tex_coord(),
+                        scale,
+                    });
+                }
             }
+            let (metallic, roughness) = material.metallic_roughness_factors();
+            let od_material = ODMaterial {
+                textures,
+                alpha_mode: material.alpha_mode(),
+                alpha_cutoff: material.alpha_cutoff(),
+                double_sided: material.double_sided(),
+                base_color: material.base_color_factor(),
+                metallic,
+                roughness,
+                emissive: material.emissive(),
+            };
             let n = materials.len();
-            materials.push(pbr_mat);
+            materials.push(od_material);
             material_use.set_use(n.into());
         }
+        if matches!(self.default_material, ODUse::Required) {
+            let n = materials.len();
+            materials.push(ODMaterial::default());
+            self.default_material.set_use(n.into());
+        }
 
         materials
     }
 
+    //ap default_material
+    /// Get the index (in the Vec<> of materials generated by
+    /// [Self::gen_materials]) of the default material, used by primitives
+    /// that do not specify a material; this is None if no such primitive is
+    /// used, or if the materials have not been generated
+    pub fn default_material(&self) -> Option<ODMaterialsIndex> {
+        self.default_material.data().copied()
+    }
+
+    //mp gen_skeletons
+    /// Generate a [mod3d_base::BoneSet] for each skin used by the objects
+    ///
+    /// The bones are the joints of the skin, with the matrix index of each
+    /// being its index in the joints of the skin (as used by the JOINTS_n
+    /// vertex attributes); the bones are related as the nearest joint
+    /// ancestor of each joint node, and the rest pose of each is the local
+    /// transformation of its node
+    pub fn gen_skeletons(&mut self, gltf: &Gltf) -> Vec<mod3d_base::BoneSet> {
+        let mut skeletons = vec![];
+        for (si, skin_use) in self.skins_used.iter_mut_required() {
+            let skin = &gltf[si];
+            let mut bone_set = mod3d_base::BoneSet::default();
+            let mut bones = vec![];
+            for (i, j) in skin.joints().iter().enumerate() {
+                bones.push(bone_set.add_bone(*gltf[*j].local_transformation(), i));
+            }
+            for (i, j) in skin.joints().iter().enumerate() {
+                let mut p = gltf[*j].parent();
+                while let Some(pn) = p {
+                    if let Some(pi) = skin.joints().iter().position(|x| *x == pn) {
+                        bone_set.relate(bones[pi], bones[i]);
+                        break;
+                    }
+                    p = gltf[pn].parent();
+                }
+            }
+            bone_set.resolve();
+            let n = skeletons.len();
+            skeletons.push(bone_set);
+            skin_use.set_use(n.into());
+        }
+        skeletons
+    }
+
+    //mp gen_inverse_bind_matrices
+    /// Generate the inverse bind matrices for each skin used by the objects,
+    /// in the same order as the skeletons from [Self::gen_skeletons]
+    ///
+    /// If a skin does not specify an accessor for its inverse bind matrices
+    /// then they are all the identity
+    ///
+    /// Should be invoked after gen_buffers has returned a Vec<> of the buffers
+    /// used by the data
+    pub fn gen_inverse_bind_matrices<'buffers, B, F>(
+        &self,
+        gltf: &Gltf,
+        buffer: &F,
+    ) -> Result<Vec<Vec<[f32; 16]>>>
+    where
+        B: AsRef<[u8]> + ?Sized + 'buffers,
+        F: Fn(usize) -> &'buffers B,
+    {
+        const IDENTITY: [f32; 16] = [
+            1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1.,
+        ];
+        let mut result = vec![];
+        for (si, _use) in self.skins_used.iter_required() {
+            let skin = &gltf[si];
+            let mut ibms = vec![IDENTITY; skin.joints().len()];
+            if let Some(a) = skin.inverse_bind_matrices() {
+                let data = gltf.accessor_f32s(a, &|b| {
+                    let bu = &self[b];
+                    bu.is_used()
+                        .then(|| buffer(bu.buffer_index().as_usize()).as_ref())
+                })?;
+                for (m, d) in ibms.iter_mut().zip(data.chunks_exact(16)) {
+                    m.copy_from_slice(d);
+                }
+            }
+            result.push(ibms);
+        }
+        Ok(result)
+    }
+
+    //ap skeleton_of_node
+    /// Get the index of the skeleton (as generated by [Self::gen_skeletons])
+    /// for a skinned mesh node, if it has one
+    pub fn skeleton_of_node(&self, gltf: &Gltf, node: NodeIndex) -> Option<ODSkinsIndex> {
+        let s = gltf[node].skin()?;
+        self.skins_used[s].data().copied()
+    }
+
     //mp gen_object
     /// Create object
     pub fn gen_object<'object, M, R>(
@@ -739,9 +1386,10 @@ impl ObjectData {
                     continue;
                 };
                 let gltf_prim = &gltf_mesh[m_pi];
-                let ia = gltf_prim.indices().unwrap();
-                let index_count = gltf[ia].count() as u32;
-                let mat_ind: Option<usize> = Some(0);
+                let index_count = index_count(gltf, gltf_prim).unwrap_or(0) as u32;
+                let mat_ind = self
+                    .primitive_material(gltf, mi, m_pi)
+                    .map(|m| m.as_usize());
                 let primitive = mod3d_base::Primitive::new(
                     gltf_prim.primitive_type(),
                     od_vi.into(),
@@ -749,10 +1397,18 @@ impl ObjectData {
                     index_count,
                     mat_ind.into(),
                 );
-                eprintln!("Add mesh {mesh:?} {m_pi} {primitive:?}");
                 mesh.add_primitive(primitive);
             }
-            object.add_component(None, Some(*node.global_transformation()), mesh);
+            // A skinned mesh is positioned only by its joints, so the
+            // transformation of its node is ignored
+            let transformation = {
+                if node.skin().is_some() {
+                    None
+                } else {
+                    Some(*node.global_transformation())
+                }
+            };
+            object.add_component(None, transformation, mesh);
         }
         object
     }
@@ -167,7 +167,7 @@ where
     type Output = ODUse<T>;
     fn index(&self, index: Index) -> &Self::Output {
         let index = index.as_usize();
-        if index > self.uses.len() {
+        if index >= self.uses.len() {
             &self.unknown
         } else {
             &self.uses[index]
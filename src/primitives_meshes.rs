@@ -5,7 +5,51 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde")]
 use crate::{deserialize, serialize};
 
-use crate::{AccessorIndex, Indexable, MaterialIndex, PrimitiveIndex};
+use crate::ValidationReport;
+use crate::{AccessorIndex, Indexable, MaterialIndex, MeshIndex, PrimitiveIndex};
+
+//a GltfAttributes
+//tp GltfAttributes
+/// The attributes of a Gltf primitive, which in Gltf Json is a map from
+/// attribute name to accessor index
+///
+/// The attributes that correspond to a mod3d_base::VertexAttr are held
+/// separately from the others - further texture coordinate, color, joint
+/// and weight sets (such as TEXCOORD_2 or JOINTS_1), and application
+/// specific attributes (whose names start with an underscore) - which are
+/// held by name
+#[derive(Debug, Default)]
+pub(crate) struct GltfAttributes {
+    /// The attributes that are mod3d_base::VertexAttr
+    pub(crate) vertex: Vec<(mod3d_base::VertexAttr, AccessorIndex)>,
+    /// The other attributes, by Gltf name, sorted by name
+    pub(crate) custom: Vec<(String, AccessorIndex)>,
+}
+
+//a GltfTargets
+//tp GltfTargets
+/// The morph targets of a Gltf primitive, each of which in Gltf Json is a
+/// map from attribute name to accessor index
+///
+/// As with [GltfAttributes] the attributes of each target that correspond
+/// to a mod3d_base::VertexAttr are held separately from the others, which
+/// are held by name; there is always the same number of each
+#[derive(Debug, Default)]
+pub(crate) struct GltfTargets {
+    /// The mod3d_base::VertexAttr attributes of each target
+    pub(crate) vertex: Vec<Vec<(mod3d_base::VertexAttr, AccessorIndex)>>,
+    /// The other attributes of each target, by Gltf name, sorted by name
+    pub(crate) custom: Vec<Vec<(String, AccessorIndex)>>,
+}
+
+//ip GltfTargets
+impl GltfTargets {
+    //ap is_empty
+    /// Return true if there are no morph targets
+    pub(crate) fn is_empty(&self) -> bool {
+        self.vertex.is_empty()
+    }
+}
 
 //a GltfPrimitive
 //tp GltfPrimitive
@@ -15,13 +59,14 @@ use crate::{AccessorIndex, Indexable, MaterialIndex, PrimitiveIndex};
 pub struct GltfPrimitive {
     // This must be a map from attribute name to accessor index
     //
-    // attribute name - corresponds to mod3d_base::VertexAttr
+    // attribute name - corresponds to mod3d_base::VertexAttr, or is a
+    // custom attribute
     #[cfg_attr(
         feature = "serde",
         serde(deserialize_with = "deserialize::attr_to_attr")
     )]
     #[cfg_attr(feature = "serde", serde(serialize_with = "serialize::attr_to_attr"))]
-    attributes: Vec<(mod3d_base::VertexAttr, AccessorIndex)>,
+    attributes: GltfAttributes,
     // 0-6: POINTS, LINES, LINE_LOOP, LINE_STRIP, TRIANGLES, TRIANGLE_STRIP,
     // TRIANGLE_FAN default is 4:triangles
     #[cfg_attr(feature = "serde", serde(default = "deserialize::pt_triangles"))]
@@ -37,7 +82,18 @@ pub struct GltfPrimitive {
     // optional - if not present then drawArrays should be used
     #[cfg_attr(feature = "serde", serde(default))]
     indices: Option<AccessorIndex>,
-    // optional: targets
+    // optional - morph targets, each a map from attribute name to the
+    // accessor index of the displacements for that attribute
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "deserialize::targets_to_attrs",
+            serialize_with = "serialize::targets_to_attrs",
+            skip_serializing_if = "GltfTargets::is_empty"
+        )
+    )]
+    targets: GltfTargets,
     // optional: extensions, extras
 }
 
@@ -74,7 +130,16 @@ impl GltfPrimitive {
     /// Return a slice of tuples of mod3d_base::VertexAttr and
     /// AccessorIndex from the Gltf for the primitive
     pub fn attributes(&self) -> &[(mod3d_base::VertexAttr, AccessorIndex)] {
-        &self.attributes
+        &self.attributes.vertex
+    }
+
+    //ap custom_attributes
+    /// Return a slice of tuples of the Gltf attribute name and
+    /// AccessorIndex for the attributes of the primitive that are not
+    /// mod3d_base::VertexAttr - such as TEXCOORD_2, COLOR_1, JOINTS_1, or
+    /// application specific attributes such as _TEMPERATURE
+    pub fn custom_attributes(&self) -> &[(String, AccessorIndex)] {
+        &self.attributes.custom
     }
 
     //ap material
@@ -84,7 +149,62 @@ impl GltfPrimitive {
         self.material
     }
     pub fn add_attribute(&mut self, attr: mod3d_base::VertexAttr, accessor: AccessorIndex) {
-        self.attributes.push((attr, accessor))
+        self.attributes.vertex.push((attr, accessor))
+    }
+
+    //mp add_custom_attribute
+    /// Add a custom attribute to the primitive, given its Gltf name (such
+    /// as TEXCOORD_2 or _TEMPERATURE)
+    pub fn add_custom_attribute<S: Into<String>>(&mut self, name: S, accessor: AccessorIndex) {
+        let name = name.into();
+        let custom = &mut self.attributes.custom;
+        let n = custom.partition_point(|(c, _)| *c < name);
+        custom.insert(n, (name, accessor))
+    }
+
+    //ap attribute
+    /// Get the accessor for an attribute of the primitive, if it has it
+    pub fn attribute(&self, attr: mod3d_base::VertexAttr) -> Option<AccessorIndex> {
+        self.attributes
+            .vertex
+            .iter()
+            .find(|(va, _)| *va == attr)
+            .map(|(_, a)| *a)
+    }
+
+    //ap custom_attribute
+    /// Get the accessor for a custom attribute of the primitive by its Gltf
+    /// name, if it has it
+    pub fn custom_attribute(&self, name: &str) -> Option<AccessorIndex> {
+        self.attributes
+            .custom
+            .iter()
+            .find(|(c, _)| c == name)
+            .map(|(_, a)| *a)
+    }
+
+    //ap targets
+    /// Return the morph targets of the primitive; each is a slice of
+    /// tuples of mod3d_base::VertexAttr and the AccessorIndex of the
+    /// displacements for that attribute
+    pub fn targets(&self) -> &[Vec<(mod3d_base::VertexAttr, AccessorIndex)>] {
+        &self.targets.vertex
+    }
+
+    //ap custom_targets
+    /// Return the attributes of the morph targets of the primitive that
+    /// are not mod3d_base::VertexAttr (such as TEXCOORD_2), in the same
+    /// order as [Self::targets]; each is a slice of tuples of the Gltf
+    /// attribute name and the AccessorIndex of the displacements
+    pub fn custom_targets(&self) -> &[Vec<(String, AccessorIndex)>] {
+        &self.targets.custom
+    }
+
+    //mp add_target
+    /// Add a morph target to the primitive
+    pub fn add_target(&mut self, target: Vec<(mod3d_base::VertexAttr, AccessorIndex)>) {
+        self.targets.vertex.push(target);
+        self.targets.custom.push(vec![]);
     }
 }
 
@@ -98,8 +218,10 @@ pub struct GltfMesh {
     name: String,
     /// The primitives that make up the mesh
     primitives: Vec<GltfPrimitive>,
-    // optional: weights (ignored as morph targets are not supported)
-    // optional: name, extensions, extras
+    /// The default weights of the morph targets of the primitives
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    weights: Vec<f32>,
+    // optional: extensions, extras
 }
 
 impl GltfMesh {
@@ -120,6 +242,123 @@ impl GltfMesh {
     pub fn primitives(&self) -> &[GltfPrimitive] {
         &self.primitives
     }
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+    pub fn set_weights(&mut self, weights: Vec<f32>) {
+        self.weights = weights;
+    }
+
+    //ap num_targets
+    /// Return the number of morph targets of the mesh - which must be the
+    /// same for every primitive
+    pub fn num_targets(&self) -> usize {
+        self.primitives.first().map_or(0, |p| p.targets().len())
+    }
+
+    //mp validate
+    /// Validate the mesh - that all of its primitives have the same number
+    /// of morph targets, that their accessors and materials are in range,
+    /// and that the weights (if any) match - adding any issues to the
+    /// report
+    pub fn validate(
+        &self,
+        m: MeshIndex,
+        num_accessors: usize,
+        num_materials: usize,
+        report: &mut ValidationReport,
+    ) {
+        let num_targets = self.num_targets();
+        for (i, p) in self.primitives.iter().enumerate() {
+            for (va, a) in p.attributes() {
+                if a.as_usize() >= num_accessors {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/meshes/{m}/primitives/{i}/attributes"),
+                        format!("{va:?} accessor {a} out of range"),
+                    );
+                }
+            }
+            for (name, a) in p.custom_attributes() {
+                if a.as_usize() >= num_accessors {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/meshes/{m}/primitives/{i}/attributes/{name}"),
+                        format!("{name} accessor {a} out of range"),
+                    );
+                }
+            }
+            if let Some(a) = p.indices() {
+                if a.as_usize() >= num_accessors {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/meshes/{m}/primitives/{i}/indices"),
+                        format!("Indices accessor {a} out of range"),
+                    );
+                }
+            }
+            if let Some(mi) = p.material() {
+                if mi.as_usize() >= num_materials {
+                    report.error(
+                        "UNRESOLVED_REFERENCE",
+                        format!("/meshes/{m}/primitives/{i}/material"),
+                        format!("Material index {mi} out of range"),
+                    );
+                }
+            }
+            if p.targets().len() != num_targets {
+                report.error(
+                    "MESH_PRIMITIVES_UNEQUAL_TARGETS_COUNT",
+                    format!("/meshes/{m}/primitives/{i}/targets"),
+                    "Primitive has a different number of morph targets to the first primitive",
+                );
+            }
+            for (ti, t) in p.targets().iter().enumerate() {
+                for (_, a) in t {
+                    if a.as_usize() >= num_accessors {
+                        report.error(
+                            "UNRESOLVED_REFERENCE",
+                            format!("/meshes/{m}/primitives/{i}/targets/{ti}"),
+                            format!("Morph target accessor {a} out of range"),
+                        );
+                    }
+                }
+            }
+            for (ti, t) in p.custom_targets().iter().enumerate() {
+                for (name, a) in t {
+                    if a.as_usize() >= num_accessors {
+                        report.error(
+                            "UNRESOLVED_REFERENCE",
+                            format!("/meshes/{m}/primitives/{i}/targets/{ti}/{name}"),
+                            format!("Morph target accessor {a} out of range"),
+                        );
+                    }
+                    report.warning(
+                        "MESH_PRIMITIVE_UNSUPPORTED_TARGET_ATTRIBUTE",
+                        format!("/meshes/{m}/primitives/{i}/targets/{ti}/{name}"),
+                        format!("Morph target attribute {name} is ignored by ObjectData"),
+                    );
+                }
+            }
+            if p.attribute(mod3d_base::VertexAttr::Position).is_none() {
+                report.warning(
+                    "MESH_PRIMITIVE_NO_POSITION",
+                    format!("/meshes/{m}/primitives/{i}/attributes"),
+                    "Primitive has no POSITION attribute, so it will not be rendered",
+                );
+            }
+        }
+        if !self.weights.is_empty() && self.weights.len() != num_targets {
+            report.error(
+                "MESH_INVALID_WEIGHTS",
+                format!("/meshes/{m}/weights"),
+                format!(
+                    "Mesh has {0} weights but {num_targets} morph targets",
+                    self.weights.len()
+                ),
+            );
+        }
+    }
 }
 
 //ip Index<PrimitiveIndex> for GltfMesh
@@ -0,0 +1,189 @@
+//a Imports
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{percent_decode, try_image_parse_base64, uri_relative_path};
+use crate::{Error, Result};
+
+//a ResourceResolver
+//tt ResourceResolver
+/// A trait for types that resolve the URIs of Gltf buffers and images to
+/// their data
+pub trait ResourceResolver {
+    /// Resolve a URI to its data
+    ///
+    /// Return Ok(None) if the URI is not one that this resolver handles (so
+    /// that another resolver may be tried), and Err() if it is but the data
+    /// could not be provided
+    fn resolve(&self, uri: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Resolve a URI to its data, failing if this resolver does not handle
+    /// it
+    fn resolve_required(&self, uri: &str) -> Result<Vec<u8>> {
+        self.resolve(uri)?
+            .ok_or_else(|| Error::UnresolvedUri(uri.into()))
+    }
+}
+
+//ip ResourceResolver for &R
+impl<R: ResourceResolver + ?Sized> ResourceResolver for &R {
+    fn resolve(&self, uri: &str) -> Result<Option<Vec<u8>>> {
+        (**self).resolve(uri)
+    }
+}
+
+//a DataUriResolver
+//tp DataUriResolver
+/// A [ResourceResolver] for base64 data: URIs (of any mime type)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DataUriResolver;
+
+//ip ResourceResolver for DataUriResolver
+impl ResourceResolver for DataUriResolver {
+    fn resolve(&self, uri: &str) -> Result<Option<Vec<u8>>> {
+        Ok(try_image_parse_base64(uri)?.map(|(data, _)| data))
+    }
+}
+
+//a DirResolver
+//tp DirResolver
+/// A [ResourceResolver] for URIs that are relative paths within a
+/// directory
+///
+/// URIs with a scheme (such as http: or data:), and those for which there
+/// is no file, are not handled; URIs that would escape the directory are
+/// errors
+#[derive(Debug, Clone)]
+pub struct DirResolver {
+    /// The directory that URIs are relative to
+    base_dir: PathBuf,
+}
+
+//ip DirResolver
+impl DirResolver {
+    //cp new
+    /// Create a new [DirResolver] for a directory
+    pub fn new<P: Into<PathBuf>>(base_dir: P) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+        }
+    }
+}
+
+//ip ResourceResolver for DirResolver
+impl ResourceResolver for DirResolver {
+    fn resolve(&self, uri: &str) -> Result<Option<Vec<u8>>> {
+        if uri.contains(':') {
+            return Ok(None);
+        }
+        match std::fs::read(uri_relative_path(&self.base_dir, uri)?) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+//a MemoryResolver
+//tp MemoryResolver
+/// A [ResourceResolver] for URIs whose data is held in memory - such as for
+/// tests, or for the contents of an archive
+///
+/// URIs are matched exactly, or after percent-decoding
+#[derive(Debug, Default, Clone)]
+pub struct MemoryResolver {
+    /// The data for each URI
+    resources: HashMap<String, Vec<u8>>,
+}
+
+//ip MemoryResolver
+impl MemoryResolver {
+    //cp new
+    /// Create a new empty [MemoryResolver]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //mp add
+    /// Add the data for a URI, replacing any that it already had
+    pub fn add<S: Into<String>>(&mut self, uri: S, data: Vec<u8>) {
+        self.resources.insert(uri.into(), data);
+    }
+}
+
+//ip ResourceResolver for MemoryResolver
+impl ResourceResolver for MemoryResolver {
+    fn resolve(&self, uri: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(data) = self.resources.get(uri) {
+            return Ok(Some(data.clone()));
+        }
+        let Ok(decoded) = percent_decode(uri) else {
+            return Ok(None);
+        };
+        Ok(self.resources.get(&decoded).cloned())
+    }
+}
+
+//a ResolverChain
+//tp ResolverChain
+/// A [ResourceResolver] that tries each of a sequence of resolvers in turn,
+/// using the first that handles a URI
+#[derive(Default)]
+pub struct ResolverChain<'a> {
+    /// The resolvers, in the order they are tried
+    resolvers: Vec<Box<dyn ResourceResolver + 'a>>,
+}
+
+//ip ResolverChain
+impl<'a> ResolverChain<'a> {
+    //cp new
+    /// Create a new empty [ResolverChain]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //cp with
+    /// Add a resolver to the end of the chain
+    pub fn with<R: ResourceResolver + 'a>(mut self, resolver: R) -> Self {
+        self.add(resolver);
+        self
+    }
+
+    //mp add
+    /// Add a resolver to the end of the chain
+    pub fn add<R: ResourceResolver + 'a>(&mut self, resolver: R) {
+        self.resolvers.push(Box::new(resolver));
+    }
+}
+
+//ip ResourceResolver for ResolverChain
+impl ResourceResolver for ResolverChain<'_> {
+    fn resolve(&self, uri: &str) -> Result<Option<Vec<u8>>> {
+        for r in &self.resolvers {
+            if let Some(data) = r.resolve(uri)? {
+                return Ok(Some(data));
+            }
+        }
+        Ok(None)
+    }
+}
+
+//a ParseResolver
+//tp ParseResolver
+/// A [ResourceResolver] that resolves data: URIs, and any other URI using a
+/// parse function invoked with a byte length of 0
+///
+/// This is used by [crate::ObjectData::gen_byte_images]; files relative to
+/// a directory should be loaded with a [DirResolver] instead
+pub(crate) struct ParseResolver<'a, BP: Fn(&str, usize) -> Result<Vec<u8>>>(pub &'a BP);
+
+//ip ResourceResolver for ParseResolver
+impl<BP: Fn(&str, usize) -> Result<Vec<u8>>> ResourceResolver for ParseResolver<'_, BP> {
+    fn resolve(&self, uri: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(data) = DataUriResolver.resolve(uri)? {
+            Ok(Some(data))
+        } else {
+            (self.0)(uri, 0).map(Some)
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//a Imports
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::{deserialize, serialize};
+
+use crate::Named;
+use crate::SamplerIndex;
+
+//a MagFilter, MinFilter, Wrap
+//tp MagFilter
+/// A magnification filter, with the GL enumeration values used in Gltf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MagFilter {
+    Nearest = 9728,
+    Linear = 9729,
+}
+
+//tp MinFilter
+/// A minification filter, with the GL enumeration values used in Gltf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinFilter {
+    Nearest = 9728,
+    Linear = 9729,
+    NearestMipmapNearest = 9984,
+    LinearMipmapNearest = 9985,
+    NearestMipmapLinear = 9986,
+    LinearMipmapLinear = 9987,
+}
+
+//tp Wrap
+/// A texture coordinate wrapping mode, with the GL enumeration values used
+/// in Gltf
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    ClampToEdge = 33071,
+    MirroredRepeat = 33648,
+    #[default]
+    Repeat = 10497,
+}
+
+//ip TryFrom<u32> for MagFilter
+impl TryFrom<u32> for MagFilter {
+    type Error = String;
+    fn try_from(value: u32) -> std::result::Result<Self, String> {
+        match value {
+            9728 => Ok(Self::Nearest),
+            9729 => Ok(Self::Linear),
+            _ => Err(format!("Unknown magnification filter {value}")),
+        }
+    }
+}
+
+//ip TryFrom<u32> for MinFilter
+impl TryFrom<u32> for MinFilter {
+    type Error = String;
+    fn try_from(value: u32) -> std::result::Result<Self, String> {
+        match value {
+            9728 => Ok(Self::Nearest),
+            9729 => Ok(Self::Linear),
+            9984 => Ok(Self::NearestMipmapNearest),
+            9985 => Ok(Self::LinearMipmapNearest),
+            9986 => Ok(Self::NearestMipmapLinear),
+            9987 => Ok(Self::LinearMipmapLinear),
+            _ => Err(format!("Unknown minification filter {value}")),
+        }
+    }
+}
+
+//ip TryFrom<u32> for Wrap
+impl TryFrom<u32> for Wrap {
+    type Error = String;
+    fn try_from(value: u32) -> std::result::Result<Self, String> {
+        match value {
+            33071 => Ok(Self::ClampToEdge),
+            33648 => Ok(Self::MirroredRepeat),
+            10497 => Ok(Self::Repeat),
+            _ => Err(format!("Unknown wrap mode {value}")),
+        }
+    }
+}
+
+//ip From<MagFilter> for u32
+impl From<MagFilter> for u32 {
+    fn from(value: MagFilter) -> u32 {
+        value as u32
+    }
+}
+
+//ip From<MinFilter> for u32
+impl From<MinFilter> for u32 {
+    fn from(value: MinFilter) -> u32 {
+        value as u32
+    }
+}
+
+//ip From<Wrap> for u32
+impl From<Wrap> for u32 {
+    fn from(value: Wrap) -> u32 {
+        value as u32
+    }
+}
+
+//ip MinFilter
+impl MinFilter {
+    //ap uses_mipmaps
+    /// Return true if the filter requires mipmaps to be generated
+    pub fn uses_mipmaps(&self) -> bool {
+        !matches!(self, Self::Nearest | Self::Linear)
+    }
+}
+
+//a GltfSampler
+//tp GltfSampler
+/// A type that contains the data from a Gltf Json 'Sampler'
+///
+/// If a filter is None then the client may choose any filtering
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfSampler {
+    /// Optional name of the sampler
+    pub name: String,
+    /// Magnification filter
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "magFilter",
+            deserialize_with = "deserialize::opt_gl_enum",
+            serialize_with = "serialize::opt_gl_enum",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub mag_filter: Option<MagFilter>,
+    /// Minification filter
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "minFilter",
+            deserialize_with = "deserialize::opt_gl_enum",
+            serialize_with = "serialize::opt_gl_enum",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub min_filter: Option<MinFilter>,
+    /// Wrapping mode for the S (U) texture coordinate
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "wrapS",
+            deserialize_with = "deserialize::gl_enum",
+            serialize_with = "serialize::gl_enum"
+        )
+    )]
+    pub wrap_s: Wrap,
+    /// Wrapping mode for the T (V) texture coordinate
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "wrapT",
+            deserialize_with = "deserialize::gl_enum",
+            serialize_with = "serialize::gl_enum"
+        )
+    )]
+    pub wrap_t: Wrap,
+    // optional: extensions, extras
+}
+
+//ip GltfSampler
+impl GltfSampler {
+    //ap mag_filter
+    pub fn mag_filter(&self) -> Option<MagFilter> {
+        self.mag_filter
+    }
+
+    //ap min_filter
+    pub fn min_filter(&self) -> Option<MinFilter> {
+        self.min_filter
+    }
+
+    //ap wrap_s
+    pub fn wrap_s(&self) -> Wrap {
+        self.wrap_s
+    }
+
+    //ap wrap_t
+    pub fn wrap_t(&self) -> Wrap {
+        self.wrap_t
+    }
+
+    //ap uses_mipmaps
+    /// Return true if the sampler requires mipmaps for its texture
+    pub fn uses_mipmaps(&self) -> bool {
+        self.min_filter.is_some_and(|f| f.uses_mipmaps())
+    }
+}
+
+//ip Named for GltfSampler
+impl Named for GltfSampler {
+    type Index = SamplerIndex;
+    fn is_name(&self, name: &str) -> bool {
+        self.name == name
+    }
+}
@@ -2,19 +2,40 @@
 use serde;
 use serde::{Serialize, Serializer};
 
+use crate::primitives_meshes::{GltfAttributes, GltfTargets};
 use crate::AccessorIndex;
 
 //a Useful functions
 //fi attr_to_attr
-/// Map an array of Gltf string attribute name/value pairs to a Vec of
-/// tuples of mod3d_base::VertexAttr and AccessorIndex
-pub fn attr_to_attr<S>(
-    attr: &[(mod3d_base::VertexAttr, AccessorIndex)],
-    ser: S,
-) -> Result<S::Ok, S::Error>
+/// Map the attributes of a primitive - both mod3d_base::VertexAttr and
+/// custom attributes - to a map of Gltf attribute name to accessor index
+pub(crate) fn attr_to_attr<S>(attr: &GltfAttributes, ser: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
+    attr_map::<S::Error>(&attr.vertex, &attr.custom)?.serialize(ser)
+}
+
+//fi attr_map
+/// Map mod3d_base::VertexAttr and custom attributes to a map of Gltf
+/// attribute name to accessor index
+fn attr_map<E: serde::ser::Error>(
+    vertex: &[(mod3d_base::VertexAttr, AccessorIndex)],
+    custom: &[(String, AccessorIndex)],
+) -> Result<std::collections::HashMap<String, usize>, E> {
+    let mut m = vertex_attr_map::<E>(vertex)?;
+    for (k, v) in custom.iter() {
+        m.insert(k.clone(), (*v).into());
+    }
+    Ok(m)
+}
+
+//fi vertex_attr_map
+/// Map an array of tuples of mod3d_base::VertexAttr and AccessorIndex to a
+/// map of Gltf attribute name to accessor index
+fn vertex_attr_map<E: serde::ser::Error>(
+    attr: &[(mod3d_base::VertexAttr, AccessorIndex)],
+) -> Result<std::collections::HashMap<String, usize>, E> {
     use std::collections::HashMap;
     let mut m = HashMap::<String, usize>::default();
     for (k, v) in attr.iter() {
@@ -30,15 +51,28 @@ where
                 TexCoords0 => "TEXCOORD_0",
                 TexCoords1 => "TEXCOORD_1",
                 _ => {
-                    return Err(serde::ser::Error::custom(format!(
-                        "Unknown Gltf attribute {k:?}"
-                    )));
+                    return Err(E::custom(format!("Unknown Gltf attribute {k:?}")));
                 }
             }
         };
         m.insert(k.to_string(), (*v).into());
     }
-    m.serialize(ser)
+    Ok(m)
+}
+
+//fi targets_to_attrs
+/// Map the morph targets of a primitive to an array of Gltf attribute
+/// name/value maps
+pub(crate) fn targets_to_attrs<S>(targets: &GltfTargets, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    use serde::ser::SerializeSeq;
+    let mut seq = ser.serialize_seq(Some(targets.vertex.len()))?;
+    for (vertex, custom) in targets.vertex.iter().zip(targets.custom.iter()) {
+        seq.serialize_element(&attr_map::<S::Error>(vertex, custom)?)?;
+    }
+    seq.end()
 }
 
 //fi primitive_type
@@ -71,29 +105,6 @@ where
     ser.serialize_u32(c)
 }
 
-//fi num_to_type
-pub fn num_to_type<S>(count: &usize, ser: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    let c = {
-        match count {
-            1 => "SCALAR",
-            2 => "VEC2",
-            3 => "VEC3",
-            4 => "VEC4", // might be MAT2...
-            9 => "MAT3",
-            16 => "MAT4",
-            _ => {
-                return Err(serde::ser::Error::custom(format!(
-                    "No GLTF scalar/vec/mat type for {count}"
-                )))
-            }
-        }
-    };
-    c.serialize(ser)
-}
-
 //fi ele_type_to_comp_type
 /// Map a Gltf JSON accessor element type integer to a BufferElementType - such
 /// as Float32
@@ -110,11 +121,11 @@ where
     let c: u32 = {
         match *ele_type {
             Int8 => 5120,
-            // Int8 => 5121, unsigned
+            UInt8 => 5121,
             Int16 => 5122,
-            // Int16 => 5123, // unsigned
-            // Int32 => 5124, // signed
-            Int32 => 5125, // unsigned
+            UInt16 => 5123,
+            Int32 => 5124,
+            UInt32 => 5125,
             Float32 => 5126,
             _ => {
                 return Err(serde::ser::Error::custom(format!(
@@ -125,3 +136,25 @@ where
     };
     c.serialize(ser)
 }
+
+//fi gl_enum
+/// Map a type to its Gltf JSON GL enumeration integer
+pub fn gl_enum<S, T>(value: &T, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Copy + Into<u32>,
+{
+    let c: u32 = (*value).into();
+    c.serialize(ser)
+}
+
+//fi opt_gl_enum
+/// Map an optional type to its Gltf JSON GL enumeration integer
+pub fn opt_gl_enum<S, T>(value: &Option<T>, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Copy + Into<u32>,
+{
+    let c: Option<u32> = value.map(|v| v.into());
+    c.serialize(ser)
+}
@@ -0,0 +1,77 @@
+//a Imports
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Named;
+use crate::{AccessorIndex, NodeIndex, SkinIndex};
+
+//a GltfSkin
+//tp GltfSkin
+/// A type that contains the data from a Gltf Json 'Skin'
+///
+/// A skin is a set of joints (nodes in the node hierarchy) that are used to
+/// pose a skinned mesh; the joints must have a common ancestor in the node
+/// hierarchy, and the 'skeleton' (if provided) should be that common root
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GltfSkin {
+    /// Optional name of the skin
+    pub name: String,
+    /// The accessor containing the inverse bind matrices (one MAT4 per
+    /// joint); if None then each is the identity matrix
+    #[cfg_attr(feature = "serde", serde(rename = "inverseBindMatrices"))]
+    pub inverse_bind_matrices: Option<AccessorIndex>,
+    /// The node that is the common root of the joints
+    pub skeleton: Option<NodeIndex>,
+    /// The joints of the skin; the index of a joint in this array is the
+    /// value used in JOINTS_n vertex attributes
+    pub joints: Vec<NodeIndex>,
+    // optional: extensions, extras
+}
+
+//ip GltfSkin
+impl GltfSkin {
+    //cp new
+    /// Create a new skin with no joints
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    //mp add_joint
+    /// Add a joint to the skin, returning its index in the skin
+    pub fn add_joint(&mut self, node: NodeIndex) -> usize {
+        let n = self.joints.len();
+        self.joints.push(node);
+        n
+    }
+
+    //ap inverse_bind_matrices
+    /// Get the accessor for the inverse bind matrices, if any
+    pub fn inverse_bind_matrices(&self) -> Option<AccessorIndex> {
+        self.inverse_bind_matrices
+    }
+
+    //ap skeleton
+    /// Get the skeleton root node, if specified
+    pub fn skeleton(&self) -> Option<NodeIndex> {
+        self.skeleton
+    }
+
+    //ap joints
+    /// Get the joints of the skin
+    pub fn joints(&self) -> &[NodeIndex] {
+        &self.joints
+    }
+}
+
+//ip Named for GltfSkin
+impl Named for GltfSkin {
+    type Index = SkinIndex;
+    fn is_name(&self, name: &str) -> bool {
+        self.name == name
+    }
+}
@@ -10,25 +10,53 @@ use crate::{ImageIndex, SamplerIndex, TextureIndex};
 /// A type representing a Gltf Texture Info, which is instantiated in
 /// different ways for different aspects of a material, and which
 /// refers to a Texture (and TexCoord number)
-#[derive(Debug, Default)]
+#[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 pub struct GltfTextureInfo {
-    /// Optional name of the texture
+    /// The texture
     pub index: TextureIndex,
-    /// Image index (source)
+    /// The texture coordinate set (n in TEXCOORD_n) used for the texture
     #[cfg_attr(feature = "serde", serde(rename = "texCoord"))]
     pub tex_coord: usize,
-    /// Scale - for normal textures only
+    /// Scale - for normal textures only; default is 1.0
     pub scale: f32,
-    /// Strength - for occlusion textures only
+    /// Strength - for occlusion textures only; default is 1.0
     pub strength: f32,
 }
 
+//ip Default for GltfTextureInfo
+impl Default for GltfTextureInfo {
+    fn default() -> Self {
+        Self {
+            index: TextureIndex::default(),
+            tex_coord: 0,
+            scale: 1.0,
+            strength: 1.0,
+        }
+    }
+}
+
 impl GltfTextureInfo {
     pub fn index(&self) -> TextureIndex {
         self.index
     }
+    //ap tex_coord
+    /// Get the texture coordinate set (n in TEXCOORD_n) used for the
+    /// texture
+    pub fn tex_coord(&self) -> usize {
+        self.tex_coord
+    }
+    //ap scale
+    /// Get the scale of a normal texture
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+    //ap strength
+    /// Get the strength of an occlusion texture
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
 }
 
 //a GltfTexture
@@ -43,15 +71,16 @@ pub struct GltfTexture {
     /// Image index (source)
     #[cfg_attr(feature = "serde", serde(rename = "source"))]
     pub image: ImageIndex,
-    /// Sampler index
-    pub sampler: SamplerIndex,
+    /// Sampler index; if None then repeat wrapping and automatic filtering
+    /// should be used
+    pub sampler: Option<SamplerIndex>,
 }
 
 impl GltfTexture {
     pub fn image(&self) -> ImageIndex {
         self.image
     }
-    pub fn sampler(&self) -> SamplerIndex {
+    pub fn sampler(&self) -> Option<SamplerIndex> {
         self.sampler
     }
 }
@@ -77,6 +77,7 @@ index_type!(TextureIndex);
 index_type!(MaterialIndex);
 index_type!(SamplerIndex);
 index_type!(PrimitiveIndex);
+index_type!(AnimationIndex);
 
 index_type!(ODBufIndex);
 index_type!(ODBufDataIndex);
@@ -85,3 +86,4 @@ index_type!(ODVerticesIndex);
 index_type!(ODImagesIndex);
 index_type!(ODTexturesIndex);
 index_type!(ODMaterialsIndex);
+index_type!(ODSkinsIndex);
@@ -22,8 +22,203 @@ pub fn try_buf_parse_base64(uri: &str, byte_length: usize) -> Result<Option<Vec<
     }
 }
 
+//fp image_mime_type
+/// Determine the mime type of encoded image data from its first bytes, for
+/// the image formats used by Gltf (PNG and JPEG, and WebP and KTX2 from
+/// extensions)
+pub fn image_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]) {
+        Some("image/png")
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if data.starts_with(&[0xab, b'K', b'T', b'X', b' ', b'2', b'0', 0xbb]) {
+        Some("image/ktx2")
+    } else {
+        None
+    }
+}
+
+//fp try_image_parse_base64
+/// Attempt to parse a URI as a data: URI containing base64 encoded image
+/// data (such as data:image/png;base64,...)
+///
+/// If it is not such a URI then return Ok(None); if it is, then parse it
+/// and return Ok(Some((data, mime type))) or Err(); the mime type is that
+/// of the URI, unless it is not an image mime type in which case it is
+/// determined from the data (and may be empty if that fails)
+pub fn try_image_parse_base64(uri: &str) -> Result<Option<(Vec<u8>, String)>> {
+    let Some(data_uri) = uri.strip_prefix("data:") else {
+        return Ok(None);
+    };
+    let Some((mime_type, data)) = data_uri.split_once(";base64,") else {
+        return Ok(None);
+    };
+    let bytes = base64_decoder::STANDARD.decode(data)?;
+    let mime_type = {
+        if mime_type.starts_with("image/") {
+            mime_type.to_string()
+        } else {
+            image_mime_type(&bytes).unwrap_or_default().to_string()
+        }
+    };
+    Ok(Some((bytes, mime_type)))
+}
+
+//fp percent_decode
+/// Decode the percent-encoded octets (such as %20) of a URI
+///
+/// Fails if a percent sign is not followed by two hex digits, or if the
+/// decoded octets are not UTF8
+pub fn percent_decode(uri: &str) -> Result<String> {
+    let bytes = uri.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            result.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        let Some(hex) = uri.get(i + 1..i + 3) else {
+            return Err(Error::BadUri(uri.into()));
+        };
+        let Ok(b) = u8::from_str_radix(hex, 16) else {
+            return Err(Error::BadUri(uri.into()));
+        };
+        result.push(b);
+        i += 3;
+    }
+    Ok(std::str::from_utf8(&result)?.to_string())
+}
+
+//fp uri_relative_path
+/// Resolve a relative URI to a path within a base directory
+///
+/// The URI is percent-decoded; it fails if it has a scheme (such as http:
+/// or data:), if it is an absolute path, or if its '..' components would
+/// escape the base directory
+pub fn uri_relative_path(base_dir: &std::path::Path, uri: &str) -> Result<std::path::PathBuf> {
+    use std::path::Component;
+    if uri.contains(':') || uri.starts_with('/') {
+        return Err(Error::BadUri(uri.into()));
+    }
+    let decoded = percent_decode(uri)?;
+    let mut path = base_dir.to_path_buf();
+    let mut depth = 0_usize;
+    for c in std::path::Path::new(&decoded).components() {
+        match c {
+            Component::Normal(c) => {
+                path.push(c);
+                depth += 1;
+            }
+            Component::CurDir => (),
+            Component::ParentDir if depth > 0 => {
+                path.pop();
+                depth -= 1;
+            }
+            _ => {
+                return Err(Error::BadUri(uri.into()));
+            }
+        }
+    }
+    Ok(path)
+}
+
 //fp buf_parse_fail
 /// Return a result that indicates a failure to parse the URI
 pub fn buf_parse_fail<T>(_uri: &str, _byte_length: usize) -> Result<T> {
     Err(Error::BufferRead)
 }
+
+//a Matrix functions
+//fp mat4_of_transformation
+/// Generate a column-major 4x4 matrix from a [mod3d_base::Transformation],
+/// which applies the scale, then rotation, then translation
+pub fn mat4_of_transformation(t: &mod3d_base::Transformation) -> [f32; 16] {
+    let (r, i, j, k) = geo_nd::quat::as_rijk(&t.rotation());
+    let s = t.scale();
+    let tr = t.translation();
+    let rot = [
+        [
+            1. - 2. * (j * j + k * k),
+            2. * (i * j - k * r),
+            2. * (i * k + j * r),
+        ],
+        [
+            2. * (i * j + k * r),
+            1. - 2. * (i * i + k * k),
+            2. * (j * k - i * r),
+        ],
+        [
+            2. * (i * k - j * r),
+            2. * (j * k + i * r),
+            1. - 2. * (i * i + j * j),
+        ],
+    ];
+    let mut m = [0.; 16];
+    for c in 0..3 {
+        for (row, rot_row) in rot.iter().enumerate() {
+            m[c * 4 + row] = rot_row[c] * s[c];
+        }
+        m[12 + c] = tr[c];
+    }
+    m[15] = 1.;
+    m
+}
+
+//fp mat4_transform_point
+/// Transform a point by a column-major 4x4 affine matrix
+pub fn mat4_transform_point(m: &[f32; 16], p: &[f32; 3]) -> [f32; 3] {
+    let mut r = [m[12], m[13], m[14]];
+    for (row, r) in r.iter_mut().enumerate() {
+        for (c, p) in p.iter().enumerate() {
+            *r += m[c * 4 + row] * p;
+        }
+    }
+    r
+}
+
+//fp mat4_affine_inverse
+/// Invert a column-major 4x4 affine matrix (one whose bottom row is 0,0,0,1)
+///
+/// If the matrix is singular then the identity is returned
+pub fn mat4_affine_inverse(m: &[f32; 16]) -> [f32; 16] {
+    let a = |r: usize, c: usize| m[c * 4 + r];
+    let cof = [
+        [
+            a(1, 1) * a(2, 2) - a(1, 2) * a(2, 1),
+            a(0, 2) * a(2, 1) - a(0, 1) * a(2, 2),
+            a(0, 1) * a(1, 2) - a(0, 2) * a(1, 1),
+        ],
+        [
+            a(1, 2) * a(2, 0) - a(1, 0) * a(2, 2),
+            a(0, 0) * a(2, 2) - a(0, 2) * a(2, 0),
+            a(0, 2) * a(1, 0) - a(0, 0) * a(1, 2),
+        ],
+        [
+            a(1, 0) * a(2, 1) - a(1, 1) * a(2, 0),
+            a(0, 1) * a(2, 0) - a(0, 0) * a(2, 1),
+            a(0, 0) * a(1, 1) - a(0, 1) * a(1, 0),
+        ],
+    ];
+    let det = a(0, 0) * cof[0][0] + a(0, 1) * cof[1][0] + a(0, 2) * cof[2][0];
+    let mut inv = [0.; 16];
+    inv[15] = 1.;
+    if det.abs() < f32::EPSILON {
+        inv[0] = 1.;
+        inv[5] = 1.;
+        inv[10] = 1.;
+        return inv;
+    }
+    for r in 0..3 {
+        for c in 0..3 {
+            inv[c * 4 + r] = cof[r][c] / det;
+        }
+    }
+    for r in 0..3 {
+        inv[12 + r] = -(0..3).map(|c| inv[c * 4 + r] * m[12 + c]).sum::<f32>();
+    }
+    inv
+}
@@ -0,0 +1,205 @@
+//a Imports
+use crate::{Error, Result};
+
+//a Severity
+//tp Severity
+/// The severity of an issue found when validating a Gltf
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Information about the Gltf that is not a problem
+    Info,
+    /// Something that is legal but probably not what was intended, or that
+    /// the specification recommends against
+    Warning,
+    /// Something that is illegal, and which would prevent the Gltf being
+    /// used
+    Error,
+}
+
+//ip Display for Severity
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+//a ValidationMode
+//tp ValidationMode
+/// How strictly a [ValidationReport] is applied when a Gltf is loaded
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Only errors cause the Gltf to be rejected
+    #[default]
+    Lenient,
+    /// Errors and warnings cause the Gltf to be rejected
+    ///
+    /// Only some of the warnings of the Khronos glTF validator are
+    /// reported - primitives without a POSITION attribute, skinned mesh
+    /// nodes that are not roots or that have a local transform, and alpha
+    /// cutoffs for materials that are not in MASK mode; morph target
+    /// attributes that are ignored by [crate::ObjectData] are also warned
+    /// about
+    Strict,
+}
+
+//a ValidationIssue
+//tp ValidationIssue
+/// An issue found when validating a Gltf
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// The severity of the issue
+    pub severity: Severity,
+    /// A machine-readable code for the issue, such as
+    /// "UNRESOLVED_REFERENCE"
+    pub code: &'static str,
+    /// The JSON pointer to the property with the issue, such as
+    /// "/accessors/3/bufferView"
+    pub pointer: String,
+    /// A description of the issue
+    pub message: String,
+}
+
+//ip Display for ValidationIssue
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} at {}: {}",
+            self.severity, self.code, self.pointer, self.message
+        )
+    }
+}
+
+//a ValidationReport
+//tp ValidationReport
+/// A report of all the issues found when validating a Gltf
+#[derive(Debug, Default, Clone)]
+pub struct ValidationReport {
+    /// The issues, in the order they were found
+    issues: Vec<ValidationIssue>,
+}
+
+//ip Display for ValidationReport
+impl std::fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} errors, {} warnings, {} infos",
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info)
+        )?;
+        for i in &self.issues {
+            write!(f, "\n{i}")?;
+        }
+        Ok(())
+    }
+}
+
+//ip ValidationReport
+impl ValidationReport {
+    //cp new
+    /// Create a new empty [ValidationReport]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //mp add
+    /// Add an issue to the report
+    pub fn add<P: Into<String>, M: Into<String>>(
+        &mut self,
+        severity: Severity,
+        code: &'static str,
+        pointer: P,
+        message: M,
+    ) {
+        self.issues.push(ValidationIssue {
+            severity,
+            code,
+            pointer: pointer.into(),
+            message: message.into(),
+        });
+    }
+
+    //mp error
+    /// Add an error to the report
+    pub fn error<P: Into<String>, M: Into<String>>(
+        &mut self,
+        code: &'static str,
+        pointer: P,
+        message: M,
+    ) {
+        self.add(Severity::Error, code, pointer, message);
+    }
+
+    //mp warning
+    /// Add a warning to the report
+    pub fn warning<P: Into<String>, M: Into<String>>(
+        &mut self,
+        code: &'static str,
+        pointer: P,
+        message: M,
+    ) {
+        self.add(Severity::Warning, code, pointer, message);
+    }
+
+    //mp info
+    /// Add an information issue to the report
+    pub fn info<P: Into<String>, M: Into<String>>(
+        &mut self,
+        code: &'static str,
+        pointer: P,
+        message: M,
+    ) {
+        self.add(Severity::Info, code, pointer, message);
+    }
+
+    //ap issues
+    /// Get the issues in the report
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    //ap count
+    /// Get the number of issues in the report of a severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+
+    //ap has_errors
+    /// Return true if the report contains any errors
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    //ap is_acceptable
+    /// Return true if the report permits the Gltf to be used in a
+    /// [ValidationMode]
+    pub fn is_acceptable(&self, mode: ValidationMode) -> bool {
+        let worst = {
+            match mode {
+                ValidationMode::Lenient => Severity::Error,
+                ValidationMode::Strict => Severity::Warning,
+            }
+        };
+        !self.issues.iter().any(|i| i.severity >= worst)
+    }
+
+    //mp into_result
+    /// Convert the report into an Err(Error::Validation) if it does not
+    /// permit the Gltf to be used in a [ValidationMode], else Ok(report)
+    pub fn into_result(self, mode: ValidationMode) -> Result<Self> {
+        if self.is_acceptable(mode) {
+            Ok(self)
+        } else {
+            Err(Error::Validation(self))
+        }
+    }
+}
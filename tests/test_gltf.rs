@@ -1,4 +1,4 @@
-use mod3d_gltf::{Error, Gltf};
+use mod3d_gltf::{Error, Gltf, GltfAccessorType};
 #[cfg(feature = "serde_json")]
 use serde_json::Value as JsonValue;
 
@@ -170,7 +170,7 @@ fn simple() -> Result<(), Error> {
     let buffers = od.gen_byte_buffers(&mut gltf, &mod3d_gltf::buf_parse_fail, None)?;
     let buffer_data =
         od.gen_buffer_data::<_, _, mod3d_base::example_client::Renderable>(&|x| &buffers[x]);
-    let buffer_accessors = od.gen_accessors(&gltf, &|x| &buffer_data[x]);
+    let buffer_accessors = od.gen_accessors(&gltf, &|x| &buffer_data[x])?;
     let vertices = od.gen_vertices(&gltf, &|x| &buffer_accessors[x]);
     let _object: mod3d_base::Object<mod3d_base::BaseMaterial, _> =
         od.gen_object(&gltf, &vertices, &[], &[]);
@@ -183,3 +183,1356 @@ fn simple() -> Result<(), Error> {
     // assert!(false);
     Ok(())
 }
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn skin() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "nodes" : [
+        { "name" : "Mesh", "mesh" : 0, "skin" : 0 },
+        { "name" : "Root", "children" : [2] },
+        { "name" : "Bone", "translation" : [0, 1, 0] },
+        { "name" : "Other" }
+    ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 } } ] } ],
+    "accessors" : [
+        { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" }
+    ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "buffers" : [ { "byteLength" : 36 } ],
+    "skins" : [ { "name" : "Skin", "skeleton" : 1, "joints" : [1, 2] } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let gltf = Gltf::of_json_value(jv)?;
+    let skin = gltf.get_skin("Skin").unwrap();
+    assert_eq!(gltf[skin].joints().len(), 2);
+    assert_eq!(gltf[skin].skeleton(), gltf.get_node("Root"));
+    assert_eq!(
+        gltf[gltf.get_node("Bone").unwrap()].parent(),
+        gltf.get_node("Root")
+    );
+
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, gltf.get_node("Mesh").unwrap());
+    assert_eq!(od.joints_used().len(), 2);
+
+    let bad_json = JSON.replace(r#""joints" : [1, 2]"#, r#""joints" : [2, 3]"#);
+    let jv = serde_json::from_str::<JsonValue>(&bad_json)?;
+    assert!(Gltf::of_json_value(jv).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn animation() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "nodes" : [ { "name" : "Moving", "scale" : [2, 2, 2] } ],
+    "accessors" : [
+        { "bufferView" : 0, "componentType" : 5126, "count" : 2, "type" : "SCALAR" },
+        { "bufferView" : 0, "byteOffset" : 8, "componentType" : 5126, "count" : 2, "type" : "VEC3" }
+    ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 32 } ],
+    "buffers" : [ {
+        "byteLength" : 32,
+        "uri" : "data:application/octet-stream;base64,AAAAAAAAgD8AAAAAAAAAAAAAAAAAAABAAAAAAAAAAAA="
+    } ],
+    "animations" : [ {
+        "name" : "Move",
+        "channels" : [ { "sampler" : 0, "target" : { "node" : 0, "path" : "translation" } } ],
+        "samplers" : [ { "input" : 0, "output" : 1 } ]
+    } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+    let data = mod3d_gltf::try_buf_parse_base64(gltf.buffers()[0].uri(), 32)?.unwrap();
+    let animation = gltf.get_animation("Move").unwrap();
+    let evaluator = mod3d_gltf::AnimationEvaluator::new(&gltf, animation, &|_| Some(&data))?;
+    assert_eq!(evaluator.duration(), 1.0);
+
+    let pose = evaluator.evaluate(&gltf, 0.5);
+    assert_eq!(pose.transformations.len(), 1);
+    let (node, t) = &pose.transformations[0];
+    assert_eq!(Some(*node), gltf.get_node("Moving"));
+    assert_eq!(t.translation(), [1., 0., 0.]);
+    assert_eq!(t.scale(), [2., 2., 2.]);
+
+    gltf.set_pose(&pose);
+    assert_eq!(
+        gltf[*node].global_transformation().translation(),
+        [1., 0., 0.]
+    );
+
+    // An unvalidated animation whose channel has no sampler is an error
+    let mut animation = mod3d_gltf::GltfAnimation::default();
+    animation.channels.push(mod3d_gltf::GltfChannel {
+        sampler: 1,
+        target: mod3d_gltf::GltfChannelTarget {
+            node: Some(0.into()),
+            ..Default::default()
+        },
+    });
+    let animation = gltf.add_animation(animation);
+    assert!(mod3d_gltf::AnimationEvaluator::new(&gltf, animation, &|_| Some(&data)).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn camera() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "scene" : 0,
+    "scenes" : [ { "nodes" : [0] } ],
+    "nodes" : [
+        { "name" : "Rig", "children" : [1], "translation" : [0, 0, 5] },
+        { "name" : "Camera", "camera" : 0 }
+    ],
+    "cameras" : [ {
+        "type" : "perspective",
+        "perspective" : { "yfov" : 1.5707963, "aspectRatio" : 1.0, "znear" : 1.0 }
+    } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let gltf = Gltf::of_json_value(jv)?;
+    let cameras = gltf.camera_nodes(gltf.scene().unwrap());
+    assert_eq!(cameras.len(), 1);
+    assert_eq!(Some(cameras[0].0), gltf.get_node("Camera"));
+    assert_eq!(cameras[0].2.translation(), [0., 0., 5.]);
+
+    let projection = gltf[cameras[0].1].projection(2.0);
+    assert!((projection[0] - 1.0).abs() < 1E-5);
+    assert!((projection[5] - 1.0).abs() < 1E-5);
+    assert_eq!(projection[10], -1.0);
+    assert_eq!(projection[11], -1.0);
+    assert_eq!(projection[14], -2.0);
+
+    let view = gltf.camera_view(cameras[0].0);
+    assert_eq!(view[14], -5.0);
+
+    // The type is required, and only the properties of that type permitted
+    let jv = serde_json::from_str::<JsonValue>(
+        r#"{ "asset" : { "version" : "2.0" }, "cameras" : [ { "perspective" : { "yfov" : 1.0, "znear" : 1.0 } } ] }"#,
+    )?;
+    assert!(matches!(Gltf::of_json_value(jv), Err(Error::Json(_))));
+    const BOTH: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "cameras" : [ {
+        "type" : "orthographic",
+        "perspective" : { "yfov" : 1.0, "znear" : 1.0 },
+        "orthographic" : { "xmag" : 1.0, "ymag" : 1.0, "znear" : 0.0, "zfar" : 10.0 }
+    } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(BOTH)?;
+    let Err(Error::Validation(report)) = Gltf::of_json_value(jv) else {
+        panic!("expected a validation report");
+    };
+    assert_eq!(report.issues()[0].code, "ONE_OF_MISMATCH");
+    assert_eq!(report.issues()[0].pointer, "/cameras/0/perspective");
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn sampler() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "samplers" : [ { "magFilter" : 9729, "minFilter" : 9987, "wrapT" : 33071 } ],
+    "images" : [ { "uri" : "a.png" } ],
+    "textures" : [ { "source" : 0, "sampler" : 0 }, { "source" : 0 } ]
+}
+"##;
+    use mod3d_gltf::{MagFilter, MinFilter, Wrap};
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let gltf = Gltf::of_json_value(jv)?;
+    let sampler = &gltf.samplers()[0];
+    assert_eq!(sampler.mag_filter(), Some(MagFilter::Linear));
+    assert_eq!(sampler.min_filter(), Some(MinFilter::LinearMipmapLinear));
+    assert_eq!(sampler.wrap_s(), Wrap::Repeat);
+    assert_eq!(sampler.wrap_t(), Wrap::ClampToEdge);
+    assert!(sampler.uses_mipmaps());
+
+    let bad_json = JSON.replace("33071", "1234");
+    let jv = serde_json::from_str::<JsonValue>(&bad_json)?;
+    assert!(Gltf::of_json_value(jv).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn morph_targets() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [
+        { "bufferView" : 0, "componentType" : 5126, "count" : 1, "type" : "VEC3" },
+        { "bufferView" : 0, "byteOffset" : 12, "componentType" : 5126, "count" : 1, "type" : "VEC3" },
+        { "bufferView" : 0, "byteOffset" : 24, "componentType" : 5126, "count" : 1, "type" : "VEC3" },
+        { "bufferView" : 0, "componentType" : 5126, "count" : 1, "type" : "VEC2" }
+    ],
+    "meshes" : [ {
+        "primitives" : [ {
+            "attributes" : { "POSITION" : 0, "TEXCOORD_0" : 3 },
+            "targets" : [ { "POSITION" : 1, "TEXCOORD_0" : 3 }, { "POSITION" : 2, "TEXCOORD_2" : 3 } ]
+        } ],
+        "weights" : [ 0.5, 0.25 ]
+    } ],
+    "nodes" : [ { "mesh" : 0 }, { "mesh" : 0, "weights" : [ 1.0, 0.0 ] } ]
+}
+"##;
+    use mod3d_base::VertexAttr;
+    use mod3d_gltf::{Severity, ValidationMode};
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let (gltf, report) = Gltf::of_json_value_with_mode(jv, ValidationMode::Lenient)?;
+    let mesh = &gltf.meshes()[0];
+    assert_eq!(mesh.num_targets(), 2);
+    let primitive = &mesh.primitives()[0];
+    assert!(primitive.targets()[0].contains(&(VertexAttr::TexCoords0, 3.into())));
+    assert_eq!(primitive.targets()[1], [(VertexAttr::Position, 2.into())]);
+    assert!(primitive.custom_targets()[0].is_empty());
+    assert_eq!(
+        primitive.custom_targets()[1],
+        [("TEXCOORD_2".to_string(), 3.into())]
+    );
+    let warnings: Vec<_> = report
+        .issues()
+        .iter()
+        .filter(|i| i.severity == Severity::Warning)
+        .map(|i| (i.code, i.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        warnings,
+        [(
+            "MESH_PRIMITIVE_UNSUPPORTED_TARGET_ATTRIBUTE",
+            "/meshes/0/primitives/0/targets/1/TEXCOORD_2"
+        )]
+    );
+
+    // Custom target attributes are written back with the others
+    let jv = serde_json::to_value(&gltf)?;
+    assert_eq!(
+        jv["meshes"][0]["primitives"][0]["targets"][1]["TEXCOORD_2"],
+        3
+    );
+    assert_eq!(gltf.node_morph_weights(0.into()), Some(&[0.5, 0.25][..]));
+    assert_eq!(gltf.node_morph_weights(1.into()), Some(&[1.0, 0.0][..]));
+
+    let bad_json = JSON.replace("[ 1.0, 0.0 ]", "[ 1.0 ]");
+    let jv = serde_json::from_str::<JsonValue>(&bad_json)?;
+    assert!(Gltf::of_json_value(jv).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn sparse() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 16, "uri" : "data:application/octet-stream;base64,AQAAAAAAgD8AAABAAABAQA==" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 4 }, { "buffer" : 0, "byteOffset" : 4, "byteLength" : 12 } ],
+    "accessors" : [ {
+        "componentType" : 5126, "count" : 2, "type" : "VEC3",
+        "sparse" : {
+            "count" : 1,
+            "indices" : { "bufferView" : 0, "componentType" : 5121 },
+            "values" : { "bufferView" : 1 }
+        }
+    } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 } } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_byte_buffers(&mut gltf, &mod3d_gltf::buf_parse_fail, None)?;
+    assert_eq!(buffers.len(), 3);
+    assert_eq!(buffers[1], [0, 0, 0, 0, 1, 0, 0, 0]);
+    let floats: Vec<f32> = buffers[2]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    assert_eq!(floats, [0., 0., 0., 1., 2., 3.]);
+
+    let bad_json = JSON.replace(r#""count" : 1,"#, r#""count" : 3,"#);
+    let jv = serde_json::from_str::<JsonValue>(&bad_json)?;
+    assert!(Gltf::of_json_value(jv).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn normalized() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 4, "uri" : "data:application/octet-stream;base64,//9AnA==" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 4 } ],
+    "accessors" : [
+        { "bufferView" : 0, "componentType" : 5123, "count" : 2, "type" : "SCALAR" },
+        { "bufferView" : 0, "componentType" : 5123, "normalized" : true, "count" : 2, "type" : "SCALAR" }
+    ]
+}
+"##;
+    use mod3d_base::BufferElementType;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let gltf = Gltf::of_json_value(jv)?;
+    assert_eq!(
+        gltf.accessors()[0].component_type(),
+        BufferElementType::UInt16
+    );
+    assert!(gltf.accessors()[1].normalized());
+
+    let data = [0xff_u8, 0xff, 0x40, 0x9c];
+    let buffer = |_| Some(&data[..]);
+    assert_eq!(gltf.accessor_f32s(0.into(), &buffer)?, [65535., 40000.]);
+    let values = gltf.accessor_f32s(1.into(), &buffer)?;
+    assert_eq!(values[0], 1.0);
+    assert!((values[1] - 40000. / 65535.).abs() < 1E-6);
+
+    let jv = serde_json::to_value(&gltf)?;
+    assert_eq!(jv["accessors"][0]["componentType"], 5123);
+    assert_eq!(jv["accessors"][0].get("normalized"), None);
+    assert_eq!(jv["accessors"][1]["normalized"], true);
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn matrix_accessors() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 20, "uri" : "data:application/octet-stream;base64,AQIAAAMEAAABAgMABAUGAAcICQA=" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 20 } ],
+    "accessors" : [
+        { "bufferView" : 0, "componentType" : 5121, "count" : 1, "type" : "MAT2" },
+        { "bufferView" : 0, "byteOffset" : 8, "componentType" : 5121, "count" : 1, "type" : "MAT3" }
+    ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let gltf = Gltf::of_json_value(jv)?;
+    let mat2 = &gltf.accessors()[0];
+    assert_eq!(mat2.accessor_type(), GltfAccessorType::Mat2);
+    assert_eq!(mat2.elements_per_data(), 4);
+    assert_eq!(mat2.ele_byte_size(), 8);
+    assert_eq!(gltf.accessors()[1].elements_per_data(), 9);
+    assert_eq!(gltf.accessors()[1].ele_byte_size(), 12);
+    assert_eq!(GltfAccessorType::Mat3.byte_length(2), 24);
+    assert_eq!(GltfAccessorType::Mat2.byte_length(2), 8);
+
+    let data = [
+        1_u8, 2, 0, 0, 3, 4, 0, 0, 1, 2, 3, 0, 4, 5, 6, 0, 7, 8, 9, 0,
+    ];
+    let buffer = |_| Some(&data[..]);
+    assert_eq!(gltf.accessor_f32s(0.into(), &buffer)?, [1., 2., 3., 4.]);
+    assert_eq!(
+        gltf.accessor_f32s(1.into(), &buffer)?,
+        [1., 2., 3., 4., 5., 6., 7., 8., 9.]
+    );
+
+    let jv = serde_json::to_value(&gltf)?;
+    assert_eq!(jv["accessors"][0]["type"], "MAT2");
+
+    const BAD: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 64 } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 64, "byteStride" : 4 } ],
+    "accessors" : [
+        { "bufferView" : 0, "byteOffset" : 2, "componentType" : 5126, "count" : 1, "type" : "MAT2", "min" : [ 0, 0 ] }
+    ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(BAD)?;
+    let Err(Error::Validation(report)) = Gltf::of_json_value(jv) else {
+        panic!("expected a validation report");
+    };
+    let codes: Vec<_> = report.issues().iter().map(|i| i.code).collect();
+    assert_eq!(
+        codes,
+        [
+            "ARRAY_LENGTH_NOT_IN_LIST",
+            "ACCESSOR_OFFSET_ALIGNMENT",
+            "ACCESSOR_SMALL_BYTESTRIDE"
+        ]
+    );
+
+    const PADDED: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 24 } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 12 }, { "buffer" : 0, "byteOffset" : 12, "byteLength" : 12 } ],
+    "accessors" : [
+        { "bufferView" : 0, "componentType" : 5126, "count" : 1, "type" : "VEC3" },
+        { "bufferView" : 1, "componentType" : 5121, "count" : 1, "type" : "MAT3" }
+    ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0, "TEXCOORD_0" : 1 } } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(PADDED)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_byte_buffers(&mut gltf, &mod3d_gltf::buf_parse_fail, Some(vec![0; 24]))?;
+    let buffer_data =
+        od.gen_buffer_data::<_, _, mod3d_base::example_client::Renderable>(&|x| &buffers[x]);
+    assert!(matches!(
+        od.gen_accessors(&gltf, &|x| &buffer_data[x]),
+        Err(Error::BadJson(_))
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn attribute_sets() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "accessors" : [
+        { "componentType" : 5126, "count" : 1, "type" : "VEC3" },
+        { "componentType" : 5126, "count" : 1, "type" : "VEC2" },
+        { "componentType" : 5126, "count" : 1, "type" : "VEC2" },
+        { "componentType" : 5126, "count" : 1, "type" : "VEC4" },
+        { "componentType" : 5123, "count" : 1, "type" : "VEC4" },
+        { "componentType" : 5126, "count" : 1, "type" : "SCALAR" }
+    ],
+    "meshes" : [ { "primitives" : [ { "attributes" : {
+        "POSITION" : 0, "TEXCOORD_0" : 1, "TEXCOORD_2" : 2, "COLOR_1" : 3, "JOINTS_1" : 4, "_TEMPERATURE" : 5
+    } } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    use mod3d_base::VertexAttr;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let gltf = Gltf::of_json_value(jv)?;
+    let p = &gltf.meshes()[0].primitives()[0];
+    assert_eq!(p.attributes().len(), 2);
+    assert_eq!(p.attribute(VertexAttr::TexCoords0), Some(1.into()));
+    let custom: Vec<_> = p
+        .custom_attributes()
+        .iter()
+        .map(|(name, a)| (name.as_str(), usize::from(*a)))
+        .collect();
+    assert_eq!(
+        custom,
+        [
+            ("COLOR_1", 3),
+            ("JOINTS_1", 4),
+            ("TEXCOORD_2", 2),
+            ("_TEMPERATURE", 5)
+        ]
+    );
+    assert_eq!(p.custom_attribute("_TEMPERATURE"), Some(5.into()));
+
+    let jv = serde_json::to_value(&gltf)?;
+    let attributes = &jv["meshes"][0]["primitives"][0]["attributes"];
+    assert_eq!(attributes["TEXCOORD_0"], 1);
+    assert_eq!(attributes["JOINTS_1"], 4);
+    assert_eq!(attributes["_TEMPERATURE"], 5);
+
+    // Accessor 0 is valid, so that only the attribute name is at fault
+    for bad in ["TEMPERATURE", "TEXCOORD_01", "COLOR_"] {
+        let json = format!(
+            r#"{{ "asset" : {{ "version" : "2.0" }},
+                "accessors" : [ {{ "componentType" : 5126, "count" : 1, "type" : "VEC3" }} ],
+                "meshes" : [ {{ "primitives" : [ {{ "attributes" : {{ "{bad}" : 0 }} }} ] }} ] }}"#
+        );
+        let jv = serde_json::from_str::<JsonValue>(&json)?;
+        assert!(
+            matches!(Gltf::of_json_value(jv), Err(Error::Json(_))),
+            "{bad} should be rejected"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn bounds() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "scene" : 0,
+    "scenes" : [ { "nodes" : [0, 1] } ],
+    "buffers" : [ { "byteLength" : 36, "uri" : "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [
+        { "bufferView" : 0, "componentType" : 5126, "count" : 2, "type" : "VEC3", "min" : [0, 0, 0], "max" : [1, 0, 0] },
+        { "bufferView" : 0, "byteOffset" : 24, "componentType" : 5126, "count" : 1, "type" : "VEC3" }
+    ],
+    "meshes" : [ {
+        "primitives" : [ { "attributes" : { "POSITION" : 0 }, "targets" : [ { "POSITION" : 1 } ] } ]
+    } ],
+    "nodes" : [ { "mesh" : 0 }, { "mesh" : 0, "translation" : [0, 0, 5] } ]
+}
+"##;
+    use mod3d_gltf::Aabb;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+    assert!(gltf.accessors()[0].has_bounds());
+    assert!(!gltf.accessors()[1].has_bounds());
+    assert_eq!(gltf.mesh_aabb(0.into()), None);
+
+    let data = mod3d_gltf::try_buf_parse_base64(gltf.buffers()[0].uri(), 36)?.unwrap();
+    gltf.compute_accessor_bounds(&|_| Some(&data[..]))?;
+    assert_eq!(gltf.accessors()[1].min(), [0., 1., 0.]);
+    assert_eq!(gltf.accessors()[1].max(), [0., 1., 0.]);
+
+    let mesh_aabb = Aabb::new([0., 0., 0.], [1., 1., 0.]);
+    assert_eq!(gltf.mesh_aabb(0.into()), Some(mesh_aabb));
+    assert_eq!(
+        gltf.node_aabb(1.into()),
+        Some(Aabb::new([0., 0., 5.], [1., 1., 5.]))
+    );
+    let scene_aabb = gltf.scene_aabb(gltf.scene().unwrap()).unwrap();
+    assert_eq!(scene_aabb, Aabb::new([0., 0., 0.], [1., 1., 5.]));
+    let sphere = gltf.scene_bounding_sphere(gltf.scene().unwrap()).unwrap();
+    assert_eq!(sphere.center, [0.5, 0.5, 2.5]);
+    assert!((sphere.radius - 27.0_f32.sqrt() * 0.5).abs() < 1E-5);
+
+    let jv = serde_json::to_value(&gltf)?;
+    assert_eq!(jv["accessors"][1]["max"][1], 1.0);
+
+    // The bounds of a normalized accessor are its raw component values
+    const NORMALIZED: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 4 } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 4 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5121, "normalized" : true, "count" : 2, "type" : "SCALAR" } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(NORMALIZED)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+    let data = [0_u8, 255, 0, 0];
+    gltf.compute_accessor_bounds(&|_| Some(&data[..]))?;
+    assert_eq!(gltf.accessors()[0].min(), [0.]);
+    assert_eq!(gltf.accessors()[0].max(), [255.]);
+    assert_eq!(
+        gltf.accessor_f32s(0.into(), &|_| Some(&data[..]))?,
+        [0., 1.]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn glb_save() -> Result<(), Error> {
+    use mod3d_base::BufferElementType;
+    use mod3d_gltf::GltfBuffer;
+    use std::io::Read;
+
+    let buffer_0: Vec<u8> = [1.0_f32, 2.0, 3.0]
+        .iter()
+        .flat_map(|f| f.to_le_bytes())
+        .chain([7, 0])
+        .collect();
+    let buffer_1: Vec<u8> = [4.0_f32, 5.0, 6.0]
+        .iter()
+        .flat_map(|f| f.to_le_bytes())
+        .collect();
+    let mut gltf = Gltf::default();
+    let b0 = gltf.add_buffer(GltfBuffer::of_base64(&buffer_0));
+    let b1 = gltf.add_buffer(GltfBuffer::of_base64(&buffer_1));
+    let v0 = gltf.add_view(b0, 0, 14, None);
+    let v1 = gltf.add_view(b1, 0, 12, None);
+    gltf.add_accessor(
+        v0,
+        0,
+        1,
+        BufferElementType::Float32,
+        false,
+        GltfAccessorType::Vec3,
+    );
+    gltf.add_accessor(
+        v1,
+        0,
+        1,
+        BufferElementType::Float32,
+        false,
+        GltfAccessorType::Vec3,
+    );
+
+    let mut glb = vec![];
+    mod3d_gltf::glb_save(&mut glb, &gltf, &[&buffer_0, &buffer_1], true)?;
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(
+        u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize,
+        glb.len()
+    );
+    assert_eq!(glb.len() % 4, 0);
+
+    let read_bin = |f: &mut std::io::Cursor<Vec<u8>>, n| {
+        let mut data = vec![0; n];
+        f.read_exact(&mut data)?;
+        Ok(Some(data))
+    };
+    let (loaded, bin) = mod3d_gltf::glb_load(&mut std::io::Cursor::new(glb), &read_bin, 1 << 20)?;
+    let bin = bin.unwrap();
+    assert_eq!(loaded.buffers().len(), 1);
+    assert_eq!(loaded.buffers()[0].byte_length(), 28);
+    assert_eq!(loaded.buffer_views()[1].buffer(), 0.into());
+    assert_eq!(loaded.buffer_views()[1].byte_offset(), 16);
+    let values = loaded.accessor_f32s(1.into(), &|_| Some(&bin[..]))?;
+    assert_eq!(values, [4.0, 5.0, 6.0]);
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn glb_chunks() -> Result<(), Error> {
+    use mod3d_gltf::{glb_load, GlbChunkType, GlbChunks};
+    use std::io::Read;
+
+    fn chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut c = (data.len() as u32).to_le_bytes().to_vec();
+        c.extend_from_slice(chunk_type);
+        c.extend_from_slice(data);
+        c
+    }
+    fn glb(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let mut glb = b"glTF\x02\x00\x00\x00".to_vec();
+        let byte_length = 12 + chunks.iter().map(|c| c.len()).sum::<usize>();
+        glb.extend_from_slice(&(byte_length as u32).to_le_bytes());
+        for c in chunks {
+            glb.extend_from_slice(c);
+        }
+        glb
+    }
+    let read_bin = |f: &mut std::io::Cursor<Vec<u8>>, n| {
+        let mut data = vec![0; n];
+        f.read_exact(&mut data)?;
+        Ok(Some(data))
+    };
+    let json = chunk(b"JSON", br#"{"asset":{"version":"2.0"}} "#);
+    let ext = chunk(b"EXT\x00", &[1, 2, 3, 4]);
+    let bin = chunk(b"BIN\x00", &[5, 6, 7, 8]);
+
+    let file = glb(&[json.clone(), ext.clone()]);
+    let (_, b) = glb_load(&mut std::io::Cursor::new(file), &read_bin, 1024)?;
+    assert!(b.is_none());
+
+    let file = glb(&[json.clone(), bin.clone(), ext.clone()]);
+    let mut cursor = std::io::Cursor::new(file.clone());
+    let chunks: Vec<_> = GlbChunks::new(&mut cursor)?.collect::<Result<_, _>>()?;
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[2].chunk_type, GlbChunkType::Unknown(0x00545845));
+    let (_, b) = glb_load(&mut std::io::Cursor::new(file.clone()), &read_bin, 1024)?;
+    assert_eq!(b, Some(vec![5, 6, 7, 8]));
+
+    let truncated = file[0..file.len() - 2].to_vec();
+    let r = glb_load(&mut std::io::Cursor::new(truncated), &read_bin, 1024);
+    assert!(matches!(r, Err(Error::GlbTruncated)));
+
+    let mut short = file.clone();
+    short[8] -= 4;
+    let r = glb_load(&mut std::io::Cursor::new(short), &read_bin, 1024);
+    assert!(matches!(r, Err(Error::GlbChunkLength)));
+
+    let unaligned = glb(&[json.clone(), chunk(b"EXT\x00", &[1, 2, 3])]);
+    let r = glb_load(&mut std::io::Cursor::new(unaligned), &read_bin, 1024);
+    assert!(matches!(r, Err(Error::GlbChunkAlignment)));
+
+    let late_bin = glb(&[json.clone(), ext, bin]);
+    let r = glb_load(&mut std::io::Cursor::new(late_bin), &read_bin, 1024);
+    assert!(matches!(r, Err(Error::GlbBinHdr)));
+
+    let r = glb_load(&mut std::io::Cursor::new(glb(&[json])), &read_bin, 16);
+    assert!(matches!(r, Err(Error::GlbJsonLength)));
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn non_indexed() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 }, "mode" : 0 } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_byte_buffers(&mut gltf, &mod3d_gltf::buf_parse_fail, None)?;
+    assert_eq!(buffers.len(), 2);
+    assert_eq!(buffers[1], [0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0]);
+    let buffer_data =
+        od.gen_buffer_data::<_, _, mod3d_base::example_client::Renderable>(&|x| &buffers[x]);
+    let buffer_accessors = od.gen_accessors(&gltf, &|x| &buffer_data[x])?;
+    assert_eq!(buffer_accessors.len(), 2);
+    let vertices = od.gen_vertices(&gltf, &|x| &buffer_accessors[x]);
+    assert_eq!(vertices.len(), 1);
+    let _object: mod3d_base::Object<mod3d_base::BaseMaterial, _> =
+        od.gen_object(&gltf, &vertices, &[], &[]);
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn materials() -> Result<(), Error> {
+    use mod3d_gltf::AlphaMode;
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "images" : [ { "uri" : "a.png" } ],
+    "textures" : [ { "source" : 0 } ],
+    "materials" : [ { "name" : "Unused" }, {
+        "name" : "Used",
+        "normalTexture" : { "index" : 0 },
+        "occlusionTexture" : { "index" : 0, "texCoord" : 1, "strength" : 0.5 },
+        "alphaMode" : "MASK",
+        "alphaCutoff" : 0.25,
+        "doubleSided" : true,
+        "pbrMetallicRoughness" : { "baseColorFactor" : [ 0.5, 0.25, 1.0, 0.75 ], "metallicFactor" : 0.0 },
+        "emissiveFactor" : [ 1.0, 0.5, 0.0 ],
+        "extensions" : { "KHR_materials_emissive_strength" : { "emissiveStrength" : 4.0 } }
+    } ],
+    "meshes" : [ { "primitives" : [
+        { "attributes" : { "POSITION" : 0 }, "material" : 1 },
+        { "attributes" : { "POSITION" : 0 } }
+    ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_byte_buffers(&mut gltf, &mod3d_gltf::buf_parse_fail, None)?;
+    let mut resolver = mod3d_gltf::MemoryResolver::new();
+    resolver.add("a.png", b"PNG!".to_vec());
+    let images = od.gen_images(&gltf, &|x| &buffers[x], &resolver, &|data, _| {
+        Ok(data.to_vec())
+    })?;
+    let textures = od.gen_textures::<_, _, mod3d_base::example_client::Renderable, _>(
+        &gltf,
+        &|x| &images[x],
+        &|image: &Vec<u8>, _| {
+            mod3d_base::Texture::new(image, (1, 1, 0), mod3d_base::BufferElementType::UInt8, 4)
+        },
+    )?;
+    assert_eq!(textures.len(), 1);
+
+    let materials = od.gen_od_materials(&gltf);
+    assert_eq!(materials.len(), 2);
+    assert_eq!(od.default_material(), Some(1.into()));
+    let used = &materials[0];
+    // The normal texture, then the occlusion texture, both of texture 0
+    assert_eq!(used.textures.len(), 2);
+    let normal = &used.textures[0];
+    assert_eq!(normal.texture, 0.into());
+    assert_eq!((normal.tex_coord, normal.scale), (0, 1.0));
+    let occlusion = &used.textures[1];
+    assert_eq!(occlusion.texture, 0.into());
+    assert_eq!(occlusion.tex_coord, 1);
+    assert_eq!(occlusion.scale, 0.5);
+    assert_eq!(used.alpha_mode, AlphaMode::Mask);
+    assert_eq!(used.alpha_cutoff, 0.25);
+    assert!(used.double_sided);
+    assert_eq!(used.base_color, [0.5, 0.25, 1.0, 0.75]);
+    assert_eq!((used.metallic, used.roughness), (0.0, 1.0));
+    assert_eq!(used.emissive, [4.0, 2.0, 0.0]);
+    let default = &materials[1];
+    assert!(default.textures.is_empty());
+    assert_eq!(default.alpha_mode, AlphaMode::Opaque);
+    assert!(!default.double_sided);
+    assert_eq!(default.base_color, [1.0; 4]);
+    assert_eq!((default.metallic, default.roughness), (1.0, 1.0));
+    assert_eq!(default.emissive, [0.0; 3]);
+
+    // The primitive with material 1 uses the first generated material,
+    // and the primitive without a material uses the default
+    let buffer_data =
+        od.gen_buffer_data::<_, _, mod3d_base::example_client::Renderable>(&|x| &buffers[x]);
+    let buffer_accessors = od.gen_accessors(&gltf, &|x| &buffer_data[x])?;
+    let vertices = od.gen_vertices(&gltf, &|x| &buffer_accessors[x]);
+    let pbr_materials: Vec<_> = materials.iter().map(|m| m.pbr_material()).collect();
+    let _object = od.gen_object(&gltf, &vertices, &textures, &pbr_materials);
+    assert_eq!(
+        od.primitive_material(&gltf, 0.into(), 0.into()),
+        Some(0.into())
+    );
+    assert_eq!(
+        od.primitive_material(&gltf, 0.into(), 1.into()),
+        od.default_material()
+    );
+
+    let material = &gltf.materials()[1];
+    let normal = material.normal_texture().as_ref().unwrap();
+    assert_eq!(normal.tex_coord(), 0);
+    assert_eq!(normal.scale(), 1.0);
+    let occlusion = material.occlusion_texture().as_ref().unwrap();
+    assert_eq!(occlusion.tex_coord(), 1);
+    assert_eq!(occlusion.strength(), 0.5);
+    assert_eq!(gltf.materials()[0].alpha_cutoff(), 0.5);
+
+    assert_eq!(gltf.materials()[0].emissive_strength(), 1.0);
+
+    let jv = serde_json::to_value(&gltf)?;
+    assert_eq!(jv["materials"][1]["alphaMode"], "MASK");
+    assert_eq!(jv["materials"][1]["alphaCutoff"], 0.25);
+    assert!(jv["materials"][0].get("alphaCutoff").is_none());
+    assert_eq!(
+        jv["materials"][1]["extensions"]["KHR_materials_emissive_strength"]["emissiveStrength"],
+        4.0
+    );
+
+    const BAD: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "materials" : [
+        { "alphaMode" : "BLEND", "alphaCutoff" : -1 },
+        { "alphaCutoff" : 0.75, "pbrMetallicRoughness" : { "baseColorFactor" : [ 1, 1, 1 ] } },
+        { "extensions" : { "KHR_materials_emissive_strength" : { "emissiveStrength" : -1 } } },
+        { "alphaCutoff" : 0.5 }
+    ]
+}
+"##;
+    use mod3d_gltf::Severity;
+    let jv = serde_json::from_str::<JsonValue>(BAD)?;
+    let Err(Error::Validation(report)) = Gltf::of_json_value(jv) else {
+        panic!("expected a validation report");
+    };
+    let issues: Vec<_> = report
+        .issues()
+        .iter()
+        .map(|i| (i.severity, i.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        issues,
+        [
+            (Severity::Error, "/materials/0/alphaCutoff"),
+            (Severity::Warning, "/materials/1/alphaCutoff"),
+            (
+                Severity::Error,
+                "/materials/1/pbrMetallicRoughness/baseColorFactor"
+            ),
+            (
+                Severity::Error,
+                "/materials/2/extensions/KHR_materials_emissive_strength/emissiveStrength"
+            ),
+            (Severity::Warning, "/materials/3/alphaCutoff"),
+        ]
+    );
+    let jv = serde_json::from_str::<JsonValue>(
+        r#"{ "asset" : { "version" : "2.0" }, "materials" : [ { "alphaMode" : "CLEAR" } ] }"#,
+    )?;
+    assert!(Gltf::of_json_value(jv).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn buffer_view_images() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 40 } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 }, { "buffer" : 0, "byteOffset" : 36, "byteLength" : 4 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "images" : [ { "bufferView" : 1, "mimeType" : "image/png" } ],
+    "textures" : [ { "source" : 0 } ],
+    "materials" : [ { "pbrMetallicRoughness" : { "baseColorTexture" : { "index" : 0 } } } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 }, "material" : 0 } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+
+    let mut buffer_0 = vec![0; 36];
+    buffer_0.extend_from_slice(b"PNG!");
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_byte_buffers(&mut gltf, &mod3d_gltf::buf_parse_fail, Some(buffer_0))?;
+    assert_eq!(buffers.len(), 2);
+    let images = od.gen_images(
+        &gltf,
+        &|x| &buffers[x],
+        &mod3d_gltf::MemoryResolver::new(),
+        &|data, mime_type| Ok((data.to_vec(), mime_type.to_string())),
+    )?;
+    assert_eq!(images, [(b"PNG!".to_vec(), "image/png".to_string())]);
+
+    let jv = serde_json::to_value(&gltf)?;
+    assert!(jv["images"][0].get("uri").is_none());
+    assert_eq!(jv["images"][0]["bufferView"], 1);
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn uri_images() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "images" : [ { "uri" : "data:application/octet-stream;base64,iVBORw0KGgo=" }, { "uri" : "texture.jpg" } ],
+    "textures" : [ { "source" : 0 }, { "source" : 1 } ],
+    "materials" : [ {
+        "pbrMetallicRoughness" : { "baseColorTexture" : { "index" : 0 } },
+        "normalTexture" : { "index" : 1 }
+    } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 }, "material" : 0 } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let dir = std::env::temp_dir().join(format!("mod3d_gltf_uri_images_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("texture.jpg"), [0xff, 0xd8, 0xff, 0xe0])?;
+
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_byte_buffers(&mut gltf, &mod3d_gltf::buf_parse_fail, None)?;
+    let resolver = mod3d_gltf::ResolverChain::new()
+        .with(mod3d_gltf::DataUriResolver)
+        .with(mod3d_gltf::DirResolver::new(dir.clone()));
+    let images = od.gen_images(&gltf, &|x| &buffers[x], &resolver, &|data, mime_type| {
+        Ok((data.len(), mime_type.to_string()))
+    })?;
+    assert_eq!(
+        images,
+        [(8, "image/png".to_string()), (4, "image/jpeg".to_string())]
+    );
+
+    // The same images through a parse function
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let image_parse =
+        |uri: &str, _: usize| -> mod3d_gltf::Result<Vec<u8>> { Ok(std::fs::read(dir.join(uri))?) };
+    let byte_images =
+        od.gen_byte_images(&gltf, &|x| &buffers[x], &image_parse, &|data, mime_type| {
+            Ok((data.len(), mime_type.to_string()))
+        })?;
+    std::fs::remove_dir_all(&dir)?;
+    assert_eq!(byte_images, images);
+
+    // Absent image properties are not written, so the Json round trips
+    let jv = serde_json::to_value(&gltf)?;
+    let image = jv["images"][1].as_object().unwrap();
+    assert_eq!(image["uri"], "texture.jpg");
+    assert!(!image.contains_key("mimeType"));
+    assert!(!image.contains_key("bufferView"));
+    let gltf = Gltf::of_json_value(jv)?;
+    assert_eq!(gltf.images()[1].mime_type(), "");
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn loader() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "my%20buffer.bin" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 } } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let dir = std::env::temp_dir().join(format!("mod3d_gltf_loader_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("model.gltf"), JSON)?;
+    std::fs::write(dir.join("my buffer.bin"), [1_u8; 36])?;
+    std::fs::write(dir.join("escape.gltf"), JSON.replace("my%20buffer", "../x"))?;
+
+    let (mut gltf, buffers) = mod3d_gltf::GltfLoader::new(dir.join("model.gltf")).load()?;
+    let escape = mod3d_gltf::GltfLoader::new(dir.join("escape.gltf")).load();
+    std::fs::remove_dir_all(&dir)?;
+    assert_eq!(buffers, [vec![1_u8; 36]]);
+    assert!(matches!(escape, Err(Error::BadUri(_))));
+    assert!(gltf.accessors()[0].has_bounds());
+    assert!(gltf.mesh_aabb(0.into()).is_some());
+
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_loaded_buffers(&mut gltf, buffers)?;
+    assert_eq!(buffers.len(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn resolvers() -> Result<(), Error> {
+    use mod3d_gltf::{DataUriResolver, MemoryResolver, ResolverChain, ResourceResolver};
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "archive/buffer%200.bin" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "images" : [ { "uri" : "data:image/jpeg;base64,/9j/4A==" } ],
+    "textures" : [ { "source" : 0 } ],
+    "materials" : [ { "pbrMetallicRoughness" : { "baseColorTexture" : { "index" : 0 } } } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 }, "material" : 0 } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let mut memory = MemoryResolver::new();
+    memory.add("archive/buffer 0.bin", vec![0; 36]);
+    let resolver = ResolverChain::new().with(DataUriResolver).with(&memory);
+    assert!(resolver.resolve("missing.bin")?.is_none());
+    assert!(matches!(
+        resolver.resolve_required("missing.bin"),
+        Err(Error::UnresolvedUri(_))
+    ));
+
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers: Vec<Vec<u8>> = od.gen_buffers(&mut gltf, &resolver, None)?;
+    assert_eq!(buffers, [vec![0; 36]]);
+    let images = od.gen_images(&gltf, &|x| &buffers[x], &resolver, &|data, mime_type| {
+        Ok((data.len(), mime_type.to_string()))
+    })?;
+    assert_eq!(images, [(4, "image/jpeg".to_string())]);
+
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers: Vec<Vec<u8>> = od.gen_buffers(&mut gltf, &resolver, None)?;
+    let result = od.gen_images(&gltf, &|x| &buffers[x], &resolver, &|_, _| {
+        Err::<(), _>("unsupported image".into())
+    });
+    let Err(Error::ImageLoad { image, source }) = result else {
+        panic!("expected an image load error");
+    };
+    assert_eq!(image, 0.into());
+    assert_eq!(source.to_string(), "unsupported image");
+    Ok(())
+}
+
+#[test]
+fn dir_resolver() -> Result<(), Error> {
+    use mod3d_gltf::{DirResolver, ResourceResolver};
+    let dir = std::env::temp_dir().join(format!("mod3d_gltf_dir_resolver_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub"))?;
+    std::fs::write(dir.join("sub").join("a b.bin"), [1, 2, 3])?;
+
+    let resolver = DirResolver::new(&dir);
+    let found = resolver.resolve("sub/a%20b.bin");
+    let up = resolver.resolve("sub/../sub/a b.bin");
+    let missing = resolver.resolve("sub/missing.bin");
+    let data_uri = resolver.resolve("data:application/octet-stream;base64,AQID");
+    let escape = resolver.resolve("../a.bin");
+    std::fs::remove_dir_all(&dir)?;
+
+    assert_eq!(found?, Some(vec![1, 2, 3]));
+    assert_eq!(up?, Some(vec![1, 2, 3]));
+    assert_eq!(missing?, None);
+    assert_eq!(data_uri?, None);
+    assert!(matches!(escape, Err(Error::BadUri(_))));
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn byte_buffers() -> Result<(), Error> {
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "model.bin" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 } } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let mut gltf = Gltf::of_json_value(jv)?;
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let parsed = std::cell::RefCell::new(vec![]);
+    let buf_parse = |uri: &str, byte_length: usize| -> mod3d_gltf::Result<Vec<u8>> {
+        parsed.borrow_mut().push((uri.to_string(), byte_length));
+        Ok(vec![0; byte_length])
+    };
+    let buffers = od.gen_byte_buffers(&mut gltf, &buf_parse, None)?;
+    assert_eq!(buffers[0].len(), 36);
+    assert_eq!(parsed.into_inner(), [("model.bin".to_string(), 36)]);
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn glb_slice() -> Result<(), Error> {
+    use std::borrow::Cow;
+    const JSON: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 36, "uri" : "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA" } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 36 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 3, "type" : "VEC3" } ],
+    "meshes" : [ { "primitives" : [ { "attributes" : { "POSITION" : 0 } } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(JSON)?;
+    let gltf = Gltf::of_json_value(jv)?;
+    let data = mod3d_gltf::try_buf_parse_base64(gltf.buffers()[0].uri(), 36)?.unwrap();
+    let mut glb = vec![];
+    mod3d_gltf::glb_save(&mut glb, &gltf, &[&data], false)?;
+
+    let (mut gltf, bin) = mod3d_gltf::glb_load_slice(&glb, 1 << 20)?;
+    let bin = bin.unwrap();
+    assert_eq!(bin, &data[..]);
+    assert!(glb.as_ptr_range().contains(&bin.as_ptr()));
+
+    let mut od = mod3d_gltf::ObjectData::new(&gltf);
+    od.add_object(&gltf, 0.into());
+    od.derive_uses(&gltf);
+    let buffers = od.gen_buffers(
+        &mut gltf,
+        &mod3d_gltf::DataUriResolver,
+        Some(Cow::Borrowed(bin)),
+    )?;
+    assert!(matches!(buffers[0], Cow::Borrowed(b) if b.as_ptr() == bin.as_ptr()));
+    let buffer_data =
+        od.gen_buffer_data::<_, _, mod3d_base::example_client::Renderable>(&|x| &*buffers[x]);
+    assert_eq!(buffer_data.len(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn glb_async() -> Result<(), Error> {
+    use std::future::Future;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    // A waker that does nothing, as reading from a slice is never pending
+    const NOOP_VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(std::ptr::null(), &NOOP_VTABLE),
+        |_| (),
+        |_| (),
+        |_| (),
+    );
+
+    fn block_on<T>(f: impl Future<Output = T>) -> T {
+        let mut f = std::pin::pin!(f);
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &NOOP_VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        match f.as_mut().poll(&mut cx) {
+            Poll::Ready(t) => t,
+            Poll::Pending => panic!("reading from a slice should never be pending"),
+        }
+    }
+
+    let data: Vec<u8> = [1.0_f32, 2.0, 3.0]
+        .iter()
+        .flat_map(|f| f.to_le_bytes())
+        .collect();
+    let mut gltf = Gltf::default();
+    let b0 = gltf.add_buffer(mod3d_gltf::GltfBuffer::of_base64(&data));
+    let v0 = gltf.add_view(b0, 0, 12, None);
+    gltf.add_accessor(
+        v0,
+        0,
+        1,
+        mod3d_base::BufferElementType::Float32,
+        false,
+        GltfAccessorType::Vec3,
+    );
+    let mut glb = vec![];
+    mod3d_gltf::glb_save(&mut glb, &gltf, &[&data], false)?;
+
+    let mut file = &glb[..];
+    let (gltf, bin) = block_on(async {
+        let mut reader = mod3d_gltf::AsyncGlbReader::new(&mut file).await?;
+        let gltf = reader.read_gltf(1 << 20).await?;
+        assert_eq!(gltf.accessors().len(), 1);
+        let byte_length = reader.bin_chunk().await?.unwrap();
+        let mut bin = vec![0; byte_length];
+        let n = reader.read_data(&mut bin[0..4]).await?;
+        reader.read_data_exact(&mut bin[n..]).await?;
+        Ok::<_, Error>((gltf, bin))
+    })?;
+    assert_eq!(bin, data);
+    assert_eq!(gltf.buffers()[0].byte_length(), 12);
+
+    let (_, bin) = block_on(mod3d_gltf::glb_load_async(&mut &glb[..], 1 << 20))?;
+    assert_eq!(bin, Some(data));
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn validation_report() -> Result<(), Error> {
+    use mod3d_gltf::{Severity, ValidationMode};
+    const BAD: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 12 } ],
+    "bufferViews" : [ { "buffer" : 1, "byteLength" : 12 }, { "buffer" : 0, "byteLength" : 24 } ],
+    "accessors" : [ { "bufferView" : 3, "componentType" : 5126, "count" : 1, "type" : "VEC3" } ],
+    "nodes" : [ { "children" : [ 5 ] } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(BAD)?;
+    let Err(Error::Validation(report)) = Gltf::of_json_value(jv) else {
+        panic!("expected a validation report");
+    };
+    assert_eq!(report.count(Severity::Error), 4);
+    let pointers: Vec<_> = report.issues().iter().map(|i| i.pointer.as_str()).collect();
+    assert_eq!(
+        pointers,
+        [
+            "/bufferViews/0/buffer",
+            "/bufferViews/1/byteLength",
+            "/accessors/0/bufferView",
+            "/nodes/0/children"
+        ]
+    );
+
+    // An accessor with no elements is an error, not a panic
+    const EMPTY: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "buffers" : [ { "byteLength" : 12 } ],
+    "bufferViews" : [ { "buffer" : 0, "byteLength" : 12 } ],
+    "accessors" : [ { "bufferView" : 0, "componentType" : 5126, "count" : 0, "type" : "VEC3" } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(EMPTY)?;
+    let Err(Error::Validation(report)) = Gltf::of_json_value(jv) else {
+        panic!("expected a validation report");
+    };
+    assert_eq!(report.count(Severity::Error), 1);
+    assert_eq!(report.issues()[0].pointer, "/accessors/0/count");
+    assert_eq!(report.issues()[0].code, "UNRESOLVED_REFERENCE");
+
+    const WARN: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "meshes" : [ { "primitives" : [ { "attributes" : { } } ] } ],
+    "nodes" : [ { "mesh" : 0 } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(WARN)?;
+    let (_, report) = Gltf::of_json_value_with_mode(jv.clone(), ValidationMode::Lenient)?;
+    assert_eq!(report.count(Severity::Warning), 1);
+    assert_eq!(
+        report.issues()[0].pointer,
+        "/meshes/0/primitives/0/attributes"
+    );
+    assert!(matches!(
+        Gltf::of_json_value_with_mode(jv, ValidationMode::Strict),
+        Err(Error::Validation(_))
+    ));
+
+    const SKINNED: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "meshes" : [ { "primitives" : [ { "attributes" : { } } ] } ],
+    "skins" : [ { "joints" : [ 0 ] } ],
+    "nodes" : [ { "children" : [ 1 ] }, { "mesh" : 0, "skin" : 0, "translation" : [ 1, 0, 0 ] } ]
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(SKINNED)?;
+    let (_, report) = Gltf::of_json_value_with_mode(jv, ValidationMode::Lenient)?;
+    let skinned: Vec<_> = report
+        .issues()
+        .iter()
+        .filter(|i| i.code.starts_with("NODE_SKINNED"))
+        .map(|i| (i.severity, i.code, i.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        skinned,
+        [
+            (Severity::Warning, "NODE_SKINNED_MESH_NON_ROOT", "/nodes/1"),
+            (
+                Severity::Warning,
+                "NODE_SKINNED_MESH_LOCAL_TRANSFORMS",
+                "/nodes/1"
+            ),
+        ]
+    );
+    Ok(())
+}
+
+//fi validation_references
+#[test]
+#[cfg(feature = "serde_json")]
+fn validation_references() -> Result<(), Error> {
+    use mod3d_gltf::Severity;
+    const BAD: &str = r##"
+{
+    "asset" : { "version" : "2.0" },
+    "meshes" : [ { "primitives" : [ { "attributes" : { }, "indices" : 0, "material" : 0 } ] } ],
+    "nodes" : [ { "mesh" : 1 }, { "mesh" : 0, "children" : [ 2 ] }, { }, { "children" : [ 2, 3 ] } ],
+    "textures" : [ { "source" : 0 } ],
+    "scenes" : [ { "nodes" : [ 0, 2, 9 ] } ],
+    "scene" : 1
+}
+"##;
+    let jv = serde_json::from_str::<JsonValue>(BAD)?;
+    let Err(Error::Validation(report)) = Gltf::of_json_value(jv) else {
+        panic!("expected a validation report");
+    };
+    let errors: Vec<_> = report
+        .issues()
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .map(|i| (i.code, i.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            ("UNRESOLVED_REFERENCE", "/meshes/0/primitives/0/indices"),
+            ("UNRESOLVED_REFERENCE", "/meshes/0/primitives/0/material"),
+            ("UNRESOLVED_REFERENCE", "/nodes/0/mesh"),
+            ("NODE_PARENT_OVERRIDE", "/nodes/3/children"),
+            ("NODE_LOOP", "/nodes/3"),
+            ("SCENE_NON_ROOT_NODE", "/scenes/0/nodes"),
+            ("UNRESOLVED_REFERENCE", "/scenes/0/nodes"),
+            ("UNRESOLVED_REFERENCE", "/scene"),
+            ("UNRESOLVED_REFERENCE", "/textures/0/source"),
+        ]
+    );
+    Ok(())
+}